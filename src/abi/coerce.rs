@@ -0,0 +1,117 @@
+use alloy_dyn_abi::{DynSolType, DynSolValue, Specifier};
+use alloy_json_abi::Param;
+use serde_json::Value;
+
+/// Coerces JSON arguments into ABI values for `params`, naming the offending
+/// argument when one doesn't match its declared type.
+pub fn coerce_args(params: &[Param], args: &[Value]) -> Result<Vec<DynSolValue>, eyre::Error> {
+    if params.len() != args.len() {
+        return Err(eyre::eyre!(
+            "Expected {} arguments ({}) but got {}",
+            params.len(),
+            params
+                .iter()
+                .map(|param| param.selector_type())
+                .collect::<Vec<_>>()
+                .join(","),
+            args.len()
+        ));
+    }
+
+    params
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(i, (param, arg))| {
+            let ty: DynSolType = param.resolve()?;
+            coerce_value(&ty, arg).map_err(|err| {
+                eyre::eyre!(
+                    "Argument {} `{} {}` is invalid: {}",
+                    i,
+                    param.selector_type(),
+                    param.name,
+                    err
+                )
+            })
+        })
+        .collect()
+}
+
+/// Coerces a single JSON value into `ty`. Arrays and tuples are given as JSON
+/// arrays, everything else as strings (or numbers/bools where that's natural).
+pub fn coerce_value(ty: &DynSolType, value: &Value) -> Result<DynSolValue, eyre::Error> {
+    match (ty, value) {
+        (DynSolType::Array(inner), Value::Array(values)) => Ok(DynSolValue::Array(
+            values
+                .iter()
+                .map(|value| coerce_value(inner, value))
+                .collect::<Result<_, _>>()?,
+        )),
+        (DynSolType::FixedArray(inner, len), Value::Array(values)) => {
+            if values.len() != *len {
+                return Err(eyre::eyre!(
+                    "Expected {} elements for {} but got {}",
+                    len,
+                    ty,
+                    values.len()
+                ));
+            }
+            Ok(DynSolValue::FixedArray(
+                values
+                    .iter()
+                    .map(|value| coerce_value(inner, value))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        (DynSolType::Tuple(types), Value::Array(values)) => {
+            if values.len() != types.len() {
+                return Err(eyre::eyre!(
+                    "Expected {} components for {} but got {}",
+                    types.len(),
+                    ty,
+                    values.len()
+                ));
+            }
+            Ok(DynSolValue::Tuple(
+                types
+                    .iter()
+                    .zip(values)
+                    .map(|(ty, value)| coerce_value(ty, value))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        (_, Value::String(s)) => Ok(ty.coerce_str(s)?),
+        // Anything beyond 64 bits has already lost precision as a JSON number
+        (_, Value::Number(n)) if n.is_u64() || n.is_i64() => Ok(ty.coerce_str(&n.to_string())?),
+        (_, Value::Number(n)) => Err(eyre::eyre!(
+            "{} is not an integer that fits in 64 bits, send it as a decimal string",
+            n
+        )),
+        (_, Value::Bool(b)) => Ok(ty.coerce_str(&b.to_string())?),
+        _ => Err(eyre::eyre!("Cannot use {} as {}", value, ty)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use serde_json::json;
+
+    #[test]
+    fn test_coerce_numbers() {
+        let uint = DynSolType::Uint(256);
+        assert_eq!(
+            coerce_value(&uint, &json!(42)).unwrap(),
+            DynSolValue::Uint(U256::from(42), 256)
+        );
+        assert_eq!(
+            coerce_value(&uint, &json!("1000000000000000000000")).unwrap(),
+            DynSolValue::Uint(U256::from(10).pow(U256::from(21)), 256)
+        );
+
+        let err = coerce_value(&uint, &json!(1e21)).unwrap_err().to_string();
+        assert!(err.contains("decimal string"), "{}", err);
+        assert!(coerce_value(&uint, &json!(1.5)).is_err());
+    }
+}
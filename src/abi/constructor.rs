@@ -0,0 +1,107 @@
+use alloy_dyn_abi::JsonAbiExt;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Bytes;
+use serde_json::Value;
+
+use super::coerce_args;
+
+/// ABI-encodes `args` against the constructor in `abi`.
+pub fn encode_constructor_args(abi: &JsonAbi, args: &[Value]) -> Result<Bytes, eyre::Error> {
+    let Some(constructor) = &abi.constructor else {
+        if args.is_empty() {
+            return Ok(Bytes::new());
+        }
+        return Err(eyre::eyre!(
+            "ABI has no constructor but {} constructor arguments were given",
+            args.len()
+        ));
+    };
+
+    let values = coerce_args(&constructor.inputs, args)
+        .map_err(|err| eyre::eyre!("Invalid constructor arguments: {}", err))?;
+    Ok(constructor.abi_encode_input(&values)?.into())
+}
+
+/// Appends the encoded constructor arguments to `initcode`. Without
+/// arguments the initcode is deployed as-is, so callers can still pre-encode
+/// them and pass the ABI only to decode calls.
+pub fn append_constructor_args(
+    initcode: Vec<u8>,
+    abi: Option<&JsonAbi>,
+    args: Option<&[Value]>,
+) -> Result<Bytes, eyre::Error> {
+    let Some(args) = args else {
+        return Ok(initcode.into());
+    };
+    let Some(abi) = abi else {
+        if !args.is_empty() {
+            return Err(eyre::eyre!("Constructor arguments require an ABI"));
+        }
+        return Ok(initcode.into());
+    };
+
+    let mut initcode = initcode;
+    initcode.extend_from_slice(&encode_constructor_args(abi, args)?);
+    Ok(initcode.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use serde_json::json;
+
+    fn abi() -> JsonAbi {
+        JsonAbi::parse(["constructor(uint256 initial, address owner)"]).unwrap()
+    }
+
+    #[test]
+    fn test_encode_constructor_args() {
+        let encoded = encode_constructor_args(
+            &abi(),
+            &[
+                json!(1),
+                json!("0x1000000000000000000000000000000000000000"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(&encoded),
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000001000000000000000000000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_encode_constructor_args_type_mismatch() {
+        let err = encode_constructor_args(&abi(), &[json!(1), json!("not an address")])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("address owner"), "{}", err);
+
+        let err = encode_constructor_args(&abi(), &[json!(1)])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("uint256,address"), "{}", err);
+    }
+
+    #[test]
+    fn test_append_constructor_args_without_args_keeps_initcode() {
+        // Initcode with its arguments already encoded, ABI only for decoding
+        let initcode = vec![0x60, 0x80, 0x00, 0x01];
+        let appended = append_constructor_args(initcode.clone(), Some(&abi()), None).unwrap();
+        assert_eq!(appended.to_vec(), initcode);
+
+        let appended = append_constructor_args(
+            initcode.clone(),
+            Some(&abi()),
+            Some(&[
+                json!(1),
+                json!("0x1000000000000000000000000000000000000000"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(appended.len(), initcode.len() + 64);
+    }
+}
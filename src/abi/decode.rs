@@ -0,0 +1,237 @@
+use alloy_dyn_abi::{DynSolValue, ErrorExt, EventExt, FunctionExt};
+use alloy_json_abi::{Error, Function, JsonAbi};
+use alloy_primitives::{hex, Address, Log};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedParam {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedLog {
+    pub address: Address,
+    pub event: String,
+    pub params: Vec<DecodedParam>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedError {
+    pub error: String,
+    pub params: Vec<DecodedParam>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DecodedResult {
+    pub function: Option<String>,
+    pub returns: Option<Vec<DecodedParam>>,
+    pub error: Option<DecodedError>,
+    pub logs: Vec<DecodedLog>,
+}
+
+/// Decodes whatever can be decoded from a call's output and logs. Anything that
+/// doesn't match `function` or the ABI is left out rather than failing the call.
+pub fn decode_result(
+    function: Option<&Function>,
+    abi: Option<&JsonAbi>,
+    output: &[u8],
+    reverted: bool,
+    logs: &[Log],
+) -> DecodedResult {
+    let returns = function
+        .filter(|_| !reverted)
+        .and_then(|function| decode_returns(function, output));
+    let error = reverted.then(|| decode_error(abi, output)).flatten();
+    let logs = abi
+        .map(|abi| logs.iter().filter_map(|log| decode_log(abi, log)).collect())
+        .unwrap_or_default();
+
+    DecodedResult {
+        function: function.map(|function| function.signature()),
+        returns,
+        error,
+        logs,
+    }
+}
+
+fn decode_returns(function: &Function, output: &[u8]) -> Option<Vec<DecodedParam>> {
+    let values = function.abi_decode_output(output, false).ok()?;
+    Some(
+        function
+            .outputs
+            .iter()
+            .zip(values)
+            .map(|(param, value)| DecodedParam {
+                name: param.name.clone(),
+                ty: param.selector_type().into_owned(),
+                value: to_json(&value),
+            })
+            .collect(),
+    )
+}
+
+fn decode_error(abi: Option<&JsonAbi>, output: &[u8]) -> Option<DecodedError> {
+    let selector = output.get(..4)?;
+    let builtins = [
+        Error::parse("Error(string message)").ok()?,
+        Error::parse("Panic(uint256 code)").ok()?,
+    ];
+    let error = abi
+        .into_iter()
+        .flat_map(|abi| abi.errors())
+        .chain(builtins.iter())
+        .find(|error| error.selector()[..] == *selector)?;
+    let decoded = error.decode_error(output).ok()?;
+
+    Some(DecodedError {
+        error: error.signature(),
+        params: error
+            .inputs
+            .iter()
+            .zip(decoded.body)
+            .map(|(param, value)| DecodedParam {
+                name: param.name.clone(),
+                ty: param.selector_type().into_owned(),
+                value: to_json(&value),
+            })
+            .collect(),
+    })
+}
+
+fn decode_log(abi: &JsonAbi, log: &Log) -> Option<DecodedLog> {
+    let topic = log.topics().first()?;
+    let event = abi
+        .events()
+        .find(|event| !event.anonymous && event.selector() == *topic)?;
+    let decoded = event
+        .decode_log_parts(log.topics().iter().copied(), &log.data.data, false)
+        .ok()?;
+
+    // Indexed and non-indexed values come back separately, re-interleave
+    // them in declaration order.
+    let mut indexed = decoded.indexed.into_iter();
+    let mut body = decoded.body.into_iter();
+    let params = event
+        .inputs
+        .iter()
+        .map(|param| {
+            let value = if param.indexed {
+                indexed.next()
+            } else {
+                body.next()
+            };
+            DecodedParam {
+                name: param.name.clone(),
+                ty: param.selector_type().into_owned(),
+                value: value.as_ref().map(to_json).unwrap_or(Value::Null),
+            }
+        })
+        .collect();
+
+    Some(DecodedLog {
+        address: log.address,
+        event: event.signature(),
+        params,
+    })
+}
+
+/// Converts an ABI value to JSON. Integers become decimal strings since they
+/// routinely overflow JSON numbers, byte values become 0x-prefixed hex.
+pub fn to_json(value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(i, _) => Value::String(i.to_string()),
+        DynSolValue::Uint(u, _) => Value::String(u.to_string()),
+        DynSolValue::FixedBytes(word, size) => Value::String(hex::encode_prefixed(&word[..*size])),
+        DynSolValue::Address(address) => Value::String(address.to_checksum(None)),
+        DynSolValue::Function(function) => Value::String(function.to_string()),
+        DynSolValue::Bytes(bytes) => Value::String(hex::encode_prefixed(bytes)),
+        DynSolValue::String(s) => Value::String(s.clone()),
+        _ => Value::Array(
+            value
+                .as_fixed_seq()
+                .or(value.as_array())
+                .unwrap_or_default()
+                .iter()
+                .map(to_json)
+                .collect(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Bytes, B256, U256};
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_log_interleaves_indexed_params() {
+        let abi = JsonAbi::parse([
+            "event Moved(address indexed from, address to, uint256 indexed amount, bool ok)",
+        ])
+        .unwrap();
+        let event = abi.events().next().unwrap();
+        let from = Address::repeat_byte(0x11);
+        let to = Address::repeat_byte(0x22);
+        let data = DynSolValue::Tuple(vec![DynSolValue::Address(to), DynSolValue::Bool(true)])
+            .abi_encode_params();
+        let log = Log::new_unchecked(
+            Address::ZERO,
+            vec![
+                event.selector(),
+                from.into_word(),
+                B256::from(U256::from(7)),
+            ],
+            Bytes::from(data),
+        );
+
+        let decoded = decode_log(&abi, &log).unwrap();
+        let values: Vec<_> = decoded.params.iter().map(|param| &param.value).collect();
+        assert_eq!(
+            values,
+            [
+                &json!(from.to_checksum(None)),
+                &json!(to.to_checksum(None)),
+                &json!("7"),
+                &json!(true),
+            ]
+        );
+        assert_eq!(decoded.params[2].name, "amount");
+    }
+
+    #[test]
+    fn test_decode_custom_and_builtin_errors() {
+        let abi = JsonAbi::parse(["error Unauthorized(address caller, uint256 role)"]).unwrap();
+        let error = abi.errors().next().unwrap();
+        let caller = Address::repeat_byte(0xaa);
+        let output = [
+            &error.selector()[..],
+            &DynSolValue::Tuple(vec![
+                DynSolValue::Address(caller),
+                DynSolValue::Uint(U256::from(3), 256),
+            ])
+            .abi_encode_params(),
+        ]
+        .concat();
+
+        let result = decode_result(None, Some(&abi), &output, true, &[]);
+        let decoded = result.error.unwrap();
+        assert_eq!(decoded.error, "Unauthorized(address,uint256)");
+        assert_eq!(decoded.params[0].value, json!(caller.to_checksum(None)));
+        assert_eq!(decoded.params[1].value, json!("3"));
+
+        // Panic(0x11), arithmetic overflow, without an ABI
+        let panic = alloy_primitives::hex::decode(
+            "4e487b710000000000000000000000000000000000000000000000000000000000000011",
+        )
+        .unwrap();
+        let decoded = decode_result(None, None, &panic, true, &[]).error.unwrap();
+        assert_eq!(decoded.error, "Panic(uint256)");
+        assert_eq!(decoded.params[0].value, json!("17"));
+    }
+}
@@ -0,0 +1,119 @@
+use alloy_dyn_abi::JsonAbiExt;
+use alloy_json_abi::{Function, JsonAbi};
+use alloy_primitives::Bytes;
+use serde_json::Value;
+
+use super::coerce_args;
+
+/// Resolves `function` to an ABI function. Full signatures such as
+/// `set(uint256)` or `get() returns (uint256)` are parsed directly, preferring
+/// the ABI's entry for the same selector since it carries parameter names.
+/// Bare names are looked up in the ABI and disambiguated by argument count.
+pub fn resolve_function(
+    abi: Option<&JsonAbi>,
+    function: &str,
+    arg_count: usize,
+) -> Result<Function, eyre::Error> {
+    if function.contains('(') {
+        let parsed = Function::parse(function)
+            .map_err(|err| eyre::eyre!("Invalid function signature `{}`: {}", function, err))?;
+        let from_abi = abi.and_then(|abi| find_function(abi, &parsed.selector()[..]));
+        return Ok(from_abi.cloned().unwrap_or(parsed));
+    }
+
+    let abi = abi.ok_or(eyre::eyre!(
+        "Function `{}` needs an ABI, or pass a full signature like `{}(uint256)`",
+        function,
+        function
+    ))?;
+    let overloads = abi
+        .function(function)
+        .ok_or(eyre::eyre!("Function `{}` not found in ABI", function))?;
+    let mut matching = overloads.iter().filter(|f| f.inputs.len() == arg_count);
+    match (matching.next(), matching.next()) {
+        (Some(f), None) => Ok(f.clone()),
+        (None, _) => Err(eyre::eyre!(
+            "No overload of `{}` takes {} arguments",
+            function,
+            arg_count
+        )),
+        (Some(_), Some(_)) => Err(eyre::eyre!(
+            "Function `{}` is ambiguous, use the full signature",
+            function
+        )),
+    }
+}
+
+/// Finds the ABI function whose selector matches the first four bytes of `calldata`.
+pub fn find_function<'a>(abi: &'a JsonAbi, calldata: &[u8]) -> Option<&'a Function> {
+    let selector = calldata.get(..4)?;
+    abi.functions().find(|f| f.selector()[..] == *selector)
+}
+
+/// Resolves `function` and ABI-encodes `args` as its calldata.
+pub fn encode_function_call(
+    abi: Option<&JsonAbi>,
+    function: &str,
+    args: &[Value],
+) -> Result<(Function, Bytes), eyre::Error> {
+    let function = resolve_function(abi, function, args.len())?;
+    let values = coerce_args(&function.inputs, args)
+        .map_err(|err| eyre::eyre!("Invalid arguments for `{}`: {}", function.signature(), err))?;
+    let calldata = function.abi_encode_input(&values)?;
+    Ok((function, calldata.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn abi() -> JsonAbi {
+        JsonAbi::parse([
+            "function set(uint256 value)",
+            "function set(uint256 key, uint256 value)",
+            "function pick(uint256 id)",
+            "function pick(address who)",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_overloads_by_arg_count() {
+        let abi = abi();
+        assert_eq!(
+            resolve_function(Some(&abi), "set", 2).unwrap().signature(),
+            "set(uint256,uint256)"
+        );
+        assert!(resolve_function(Some(&abi), "set", 3).is_err());
+
+        let err = resolve_function(Some(&abi), "pick", 1)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ambiguous"), "{}", err);
+        assert_eq!(
+            resolve_function(Some(&abi), "pick(address)", 1)
+                .unwrap()
+                .inputs[0]
+                .name,
+            "who"
+        );
+
+        assert!(resolve_function(None, "set", 1).is_err());
+        assert_eq!(
+            resolve_function(None, "set(uint256)", 1)
+                .unwrap()
+                .signature(),
+            "set(uint256)"
+        );
+    }
+
+    #[test]
+    fn test_encode_function_call() {
+        let (function, calldata) = encode_function_call(None, "set(uint256)", &[json!(1)]).unwrap();
+        assert_eq!(&calldata[..4], &function.selector()[..]);
+        assert_eq!(calldata.len(), 4 + 32);
+        assert_eq!(calldata[35], 1);
+        assert_eq!(find_function(&abi(), &calldata).unwrap().inputs.len(), 1);
+    }
+}
@@ -0,0 +1,8 @@
+mod coerce;
+mod constructor;
+mod decode;
+mod function;
+pub use coerce::{coerce_args, coerce_value};
+pub use constructor::{append_constructor_args, encode_constructor_args};
+pub use decode::{decode_result, to_json, DecodedError, DecodedLog, DecodedParam, DecodedResult};
+pub use function::{encode_function_call, find_function, resolve_function};
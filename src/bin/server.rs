@@ -1,11 +1,20 @@
 use gas_exp::routes::{
-    byterace_route, compile_solidity_route, execute_calldatas_fork_route, execute_calldatas_route,
+    assemble_route, byterace_route, compare_route, compile_and_run_route, compile_route,
+    compile_solidity_route, compile_yul_route, create_session_route, delete_session_route,
+    disassemble_route, execute_calldatas_fork_route, execute_calldatas_route,
+    gas_report_fork_route, gas_report_route, libraries_route, list_sessions_route,
+    session_call_route, session_deploy_route, solc_versions_route, source_gas_route, verify_route,
 };
+use gas_exp::session::SessionStore;
 use rocket_cors::{AllowedHeaders, AllowedOrigins, CorsOptions};
+use std::{env, time::Duration};
 
 #[macro_use]
 extern crate rocket;
 
+/// Sessions idle for longer than this are evicted, override with `SESSION_TTL_SECS`.
+const DEFAULT_SESSION_TTL_SECS: u64 = 30 * 60;
+
 #[launch]
 fn rocket() -> _ {
     // Configure CORS options
@@ -14,13 +23,38 @@ fn rocket() -> _ {
         .allowed_headers(AllowedHeaders::all())
         .allow_credentials(true);
 
-    rocket::build().attach(cors.to_cors().unwrap()).mount(
-        "/",
-        routes![
-            execute_calldatas_route,
-            compile_solidity_route,
-            execute_calldatas_fork_route,
-            byterace_route
-        ],
-    )
+    let session_ttl = env::var("SESSION_TTL_SECS")
+        .ok()
+        .and_then(|secs| secs.parse().ok())
+        .unwrap_or(DEFAULT_SESSION_TTL_SECS);
+
+    rocket::build()
+        .attach(cors.to_cors().unwrap())
+        .manage(SessionStore::new(Duration::from_secs(session_ttl)))
+        .mount(
+            "/",
+            routes![
+                execute_calldatas_route,
+                compile_solidity_route,
+                execute_calldatas_fork_route,
+                byterace_route,
+                create_session_route,
+                list_sessions_route,
+                delete_session_route,
+                session_deploy_route,
+                session_call_route,
+                compare_route,
+                gas_report_route,
+                gas_report_fork_route,
+                source_gas_route,
+                compile_and_run_route,
+                solc_versions_route,
+                compile_route,
+                compile_yul_route,
+                assemble_route,
+                disassemble_route,
+                verify_route,
+                libraries_route
+            ],
+        )
 }
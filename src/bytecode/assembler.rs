@@ -0,0 +1,279 @@
+use alloy_primitives::{Bytes, U256};
+use revm::{
+    interpreter::opcode::{self, OpCode},
+    primitives::SpecId,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Length of the initcode that copies the runtime code into memory and returns
+/// it.
+pub const INITCODE_PREFIX_LEN: usize = 12;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Assembled {
+    pub initcode: Bytes,
+    pub runtime: Bytes,
+    /// Offset of every label's JUMPDEST in the runtime code.
+    pub labels: BTreeMap<String, usize>,
+}
+
+enum Item {
+    Op(u8),
+    /// PUSH of a literal, with its size fixed by the mnemonic or inferred.
+    Push(U256, usize),
+    /// PUSH of a label's offset. Without a fixed size it starts at one byte and
+    /// grows until every offset fits.
+    PushLabel(String, Option<usize>),
+    Label(String),
+}
+
+/// Assembles mnemonics into runtime code, plus initcode that deploys it.
+///
+/// One instruction per whitespace-separated token, `;` or `//` start comments.
+/// `name:` defines a label and emits its JUMPDEST. `PUSH x` infers the push
+/// size from `x`, which is a number or a label, `PUSH1`..`PUSH32` fix it.
+/// `PUSH 0` becomes PUSH0 from Shanghai on and `PUSH1 0x00` before it.
+pub fn assemble(source: &str, spec_id: SpecId) -> Result<Assembled, eyre::Error> {
+    let push0 = SpecId::enabled(spec_id, SpecId::SHANGHAI);
+    let items = parse(source, push0)?;
+    let mut label_sizes: Vec<usize> = items
+        .iter()
+        .filter_map(|item| match item {
+            Item::PushLabel(_, size) => Some(size.unwrap_or(1)),
+            _ => None,
+        })
+        .collect();
+
+    // Label pushes only ever grow, so this settles.
+    let labels = loop {
+        let labels = layout(&items, &label_sizes)?;
+        let mut grown = false;
+        let mut sizes = label_sizes.iter_mut();
+        for item in &items {
+            if let Item::PushLabel(name, fixed) = item {
+                let size = sizes.next().expect("one size per label push");
+                let needed = byte_len(U256::from(labels[name]));
+                if needed > *size {
+                    if fixed.is_some() {
+                        return Err(eyre::eyre!("Label {} doesn't fit in PUSH{}", name, size));
+                    }
+                    *size = needed;
+                    grown = true;
+                }
+            }
+        }
+        if !grown {
+            break labels;
+        }
+    };
+
+    let mut runtime = vec![];
+    let mut sizes = label_sizes.iter();
+    for item in &items {
+        match item {
+            Item::Op(op) => runtime.push(*op),
+            Item::Label(_) => runtime.push(opcode::JUMPDEST),
+            Item::Push(value, size) => push(&mut runtime, *value, *size),
+            Item::PushLabel(name, _) => {
+                let size = *sizes.next().expect("one size per label push");
+                push(&mut runtime, U256::from(labels[name]), size);
+            }
+        }
+    }
+
+    let len = runtime.len();
+    if len > 0xffff {
+        return Err(eyre::eyre!(
+            "Runtime code is {} bytes, at most 65535 are supported",
+            len
+        ));
+    }
+    let [len_hi, len_lo] = (len as u16).to_be_bytes();
+    let mut initcode = vec![
+        opcode::PUSH2,
+        len_hi,
+        len_lo,
+        opcode::DUP1,
+        opcode::PUSH1,
+        INITCODE_PREFIX_LEN as u8,
+        opcode::PUSH1,
+        0,
+        opcode::CODECOPY,
+        opcode::PUSH1,
+        0,
+        opcode::RETURN,
+    ];
+    initcode.extend_from_slice(&runtime);
+
+    Ok(Assembled {
+        initcode: initcode.into(),
+        runtime: runtime.into(),
+        labels,
+    })
+}
+
+fn parse(source: &str, push0: bool) -> Result<Vec<Item>, eyre::Error> {
+    let mut items = vec![];
+    for (line_number, line) in source.lines().enumerate() {
+        let line = line.split(';').next().unwrap_or_default();
+        let line = line.split("//").next().unwrap_or_default();
+        let mut tokens = line.split_whitespace();
+        let error = |message: String| eyre::eyre!("Line {}: {}", line_number + 1, message);
+
+        while let Some(token) = tokens.next() {
+            if let Some(label) = token.strip_suffix(':') {
+                items.push(Item::Label(label.to_string()));
+                continue;
+            }
+
+            let mnemonic = token.to_uppercase();
+            let fixed_size = match mnemonic.strip_prefix("PUSH") {
+                Some("") => None,
+                Some("0") if !push0 => {
+                    return Err(error("PUSH0 needs Shanghai or later".to_string()))
+                }
+                Some("0") => {
+                    items.push(Item::Op(opcode::PUSH0));
+                    continue;
+                }
+                Some(size) => match size.parse::<usize>() {
+                    Ok(size @ 1..=32) => Some(size),
+                    _ => return Err(error(format!("Unknown opcode {}", token))),
+                },
+                None => {
+                    let op = OpCode::parse(&mnemonic)
+                        .ok_or_else(|| error(format!("Unknown opcode {}", token)))?;
+                    if op.info().immediate_size() > 0 {
+                        return Err(error(format!("{} is not supported", mnemonic)));
+                    }
+                    items.push(Item::Op(op.get()));
+                    continue;
+                }
+            };
+
+            let operand = tokens
+                .next()
+                .ok_or_else(|| error(format!("{} needs an operand", mnemonic)))?;
+            let value = match operand.strip_prefix("0x") {
+                Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                None if operand.starts_with(|c: char| c.is_ascii_digit()) => {
+                    U256::from_str_radix(operand, 10).ok()
+                }
+                None => {
+                    items.push(Item::PushLabel(operand.to_string(), fixed_size));
+                    continue;
+                }
+            }
+            .ok_or_else(|| error(format!("Invalid number {}", operand)))?;
+
+            let size = match fixed_size {
+                Some(size) if byte_len(value) > size => {
+                    return Err(error(format!("{} doesn't fit in PUSH{}", operand, size)))
+                }
+                Some(size) => size,
+                // PUSH0 for zero, which needs Shanghai
+                None if value.is_zero() && push0 => {
+                    items.push(Item::Op(opcode::PUSH0));
+                    continue;
+                }
+                None => byte_len(value),
+            };
+            items.push(Item::Push(value, size));
+        }
+    }
+    Ok(items)
+}
+
+/// Offsets of every label, given the current size of every label push.
+fn layout(items: &[Item], label_sizes: &[usize]) -> Result<BTreeMap<String, usize>, eyre::Error> {
+    let mut labels = BTreeMap::new();
+    let mut pc = 0;
+    let mut sizes = label_sizes.iter();
+    for item in items {
+        match item {
+            Item::Op(_) => pc += 1,
+            Item::Push(_, size) => pc += 1 + size,
+            Item::PushLabel(..) => pc += 1 + sizes.next().expect("one size per label push"),
+            Item::Label(name) => {
+                if labels.insert(name.clone(), pc).is_some() {
+                    return Err(eyre::eyre!("Label {} is defined twice", name));
+                }
+                pc += 1;
+            }
+        }
+    }
+
+    for item in items {
+        if let Item::PushLabel(name, _) = item {
+            if !labels.contains_key(name) {
+                return Err(eyre::eyre!("Undefined label {}", name));
+            }
+        }
+    }
+    Ok(labels)
+}
+
+fn byte_len(value: U256) -> usize {
+    value.byte_len().max(1)
+}
+
+fn push(code: &mut Vec<u8>, value: U256, size: usize) {
+    code.push(opcode::PUSH1 + size as u8 - 1);
+    code.extend_from_slice(&value.to_be_bytes::<32>()[32 - size..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_resolves_labels_and_infers_push_sizes() {
+        let assembled = assemble(
+            "
+            PUSH 0x2a      ; PUSH1
+            PUSH 0         // PUSH0
+            PUSH end
+            JUMP
+            end:
+            PUSH2 1
+            STOP
+            ",
+            SpecId::CANCUN,
+        )
+        .unwrap();
+
+        assert_eq!(assembled.labels["end"], 6);
+        assert_eq!(hex::encode(&assembled.runtime), "602a5f6006565b61000100");
+        assert_eq!(
+            hex::encode(&assembled.initcode[..INITCODE_PREFIX_LEN]),
+            "61000b80600c6000396000f3"
+        );
+    }
+
+    #[test]
+    fn test_grows_label_pushes_past_one_byte() {
+        let source = format!("PUSH far\nJUMP\n{}\nfar:", "JUMPDEST\n".repeat(300));
+        let assembled = assemble(&source, SpecId::CANCUN).unwrap();
+        assert_eq!(assembled.labels["far"], 3 + 1 + 300);
+        assert_eq!(assembled.runtime[0], opcode::PUSH2);
+    }
+
+    #[test]
+    fn test_rejects_undefined_labels() {
+        assert!(assemble("PUSH nowhere JUMP", SpecId::CANCUN).is_err());
+    }
+
+    #[test]
+    fn test_push_zero_before_shanghai() {
+        let assembled = assemble("PUSH 0 PUSH1 0", SpecId::MERGE).unwrap();
+        assert_eq!(hex::encode(&assembled.runtime), "60006000");
+        assert!(assemble("PUSH0", SpecId::MERGE).is_err());
+        assert_eq!(
+            hex::encode(assemble("PUSH 0", SpecId::SHANGHAI).unwrap().runtime),
+            "5f"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+use alloy_primitives::{Bytes, Selector};
+use revm::interpreter::opcode::{self, OpCode};
+use serde::Serialize;
+
+use super::{decode_metadata, split_metadata};
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Instruction {
+    pub pc: usize,
+    pub opcode: u8,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub immediate: Option<Bytes>,
+    /// `None` for opcodes that aren't defined.
+    pub static_gas: Option<u64>,
+    /// Whether the opcode costs more than `staticGas` depending on its inputs
+    /// or on warm/cold access.
+    pub dynamic_gas: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BasicBlock {
+    pub start: usize,
+    /// pc of the block's last instruction.
+    pub end: usize,
+    pub static_gas: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatcherEntry {
+    pub selector: Selector,
+    pub target: Option<usize>,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Disassembly {
+    pub instructions: Vec<Instruction>,
+    pub basic_blocks: Vec<BasicBlock>,
+    pub jump_destinations: Vec<usize>,
+    pub selectors: Vec<DispatcherEntry>,
+    /// Trailing CBOR metadata, not disassembled.
+    pub metadata: Option<Bytes>,
+}
+
+pub fn disassemble(code: &[u8]) -> Disassembly {
+    // Initcode may end in constructor arguments that only look like metadata,
+    // so only strip what decodes as compiler metadata
+    let (code, metadata) = match split_metadata(code) {
+        (stripped, Some(metadata))
+            if decode_metadata(metadata)
+                .is_ok_and(|decoded| decoded.solc.is_some() || decoded.vyper.is_some()) =>
+        {
+            (stripped, Some(metadata))
+        }
+        _ => (code, None),
+    };
+    let instructions = instructions(code);
+    Disassembly {
+        basic_blocks: basic_blocks(&instructions),
+        jump_destinations: instructions
+            .iter()
+            .filter(|instruction| instruction.opcode == opcode::JUMPDEST)
+            .map(|instruction| instruction.pc)
+            .collect(),
+        selectors: selectors(&instructions),
+        metadata: metadata.map(Bytes::copy_from_slice),
+        instructions,
+    }
+}
+
+fn instructions(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = vec![];
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        let known = OpCode::new(op);
+        // Only pushes carry immediates in legacy code
+        let immediate_size = match op {
+            opcode::PUSH1..=opcode::PUSH32 => (op - opcode::PUSH0) as usize,
+            _ => 0,
+        };
+        let immediate = (immediate_size > 0).then(|| {
+            let end = (pc + 1 + immediate_size).min(code.len());
+            Bytes::copy_from_slice(&code[pc + 1..end])
+        });
+        let (static_gas, dynamic_gas) = match known.and_then(|_| static_gas(op)) {
+            Some((gas, dynamic)) => (Some(gas), dynamic),
+            None => (None, false),
+        };
+        instructions.push(Instruction {
+            pc,
+            opcode: op,
+            name: known.map_or_else(|| format!("UNKNOWN(0x{:02x})", op), |op| op.as_str().into()),
+            immediate,
+            static_gas,
+            dynamic_gas,
+        });
+        pc += 1 + immediate_size;
+    }
+    instructions
+}
+
+/// Blocks start at every JUMPDEST and after every instruction that jumps or
+/// stops.
+fn basic_blocks(instructions: &[Instruction]) -> Vec<BasicBlock> {
+    let mut blocks: Vec<BasicBlock> = vec![];
+    let mut starts_block = true;
+    for instruction in instructions {
+        if starts_block || instruction.opcode == opcode::JUMPDEST {
+            blocks.push(BasicBlock {
+                start: instruction.pc,
+                end: instruction.pc,
+                static_gas: 0,
+            });
+        }
+        let block = blocks.last_mut().expect("first instruction starts a block");
+        block.end = instruction.pc;
+        block.static_gas += instruction.static_gas.unwrap_or(0);
+        starts_block = ends_block(instruction.opcode);
+    }
+    blocks
+}
+
+fn ends_block(op: u8) -> bool {
+    OpCode::new(op).map_or(true, |known| known.info().is_terminating())
+        || matches!(op, opcode::JUMP | opcode::JUMPI)
+}
+
+/// Finds solc's dispatcher pattern, `PUSH4 selector (DUP2) EQ PUSHn target JUMPI`.
+fn selectors(instructions: &[Instruction]) -> Vec<DispatcherEntry> {
+    let mut selectors = vec![];
+    for (i, instruction) in instructions.iter().enumerate() {
+        let Some(selector) = instruction
+            .immediate
+            .as_ref()
+            .filter(|_| instruction.opcode == opcode::PUSH4)
+            .and_then(|immediate| Selector::try_from(immediate.as_ref()).ok())
+        else {
+            continue;
+        };
+        let mut rest = instructions[i + 1..].iter().peekable();
+        if rest.peek().is_some_and(|next| next.opcode == opcode::DUP2) {
+            rest.next();
+        }
+        if !rest.next().is_some_and(|next| next.opcode == opcode::EQ) {
+            continue;
+        }
+        let target = rest
+            .next()
+            .filter(|push| (opcode::PUSH1..=opcode::PUSH32).contains(&push.opcode));
+        if !rest.next().is_some_and(|jump| jump.opcode == opcode::JUMPI) {
+            continue;
+        }
+        selectors.push(DispatcherEntry {
+            selector,
+            target: target.and_then(|push| push.immediate.as_ref()).map(|dest| {
+                dest.iter()
+                    .fold(0usize, |acc, byte| acc << 8 | *byte as usize)
+            }),
+        });
+    }
+    selectors
+}
+
+/// Base gas of an opcode as of Cancun, and whether it can cost more.
+fn static_gas(op: u8) -> Option<(u64, bool)> {
+    use opcode::*;
+    let gas = match op {
+        STOP | RETURN | REVERT => (0, op != STOP),
+        JUMPDEST => (1, false),
+        ADDRESS | ORIGIN | CALLER | CALLVALUE | CALLDATASIZE | CODESIZE | GASPRICE
+        | RETURNDATASIZE | COINBASE | TIMESTAMP | NUMBER | DIFFICULTY | GASLIMIT | CHAINID
+        | BASEFEE | BLOBBASEFEE | POP | PC | MSIZE | GAS | PUSH0 => (2, false),
+        ADD | SUB | LT | GT | SLT | SGT | EQ | ISZERO | AND | OR | XOR | NOT | BYTE | SHL | SHR
+        | SAR | CALLDATALOAD | BLOBHASH => (3, false),
+        MLOAD | MSTORE | MSTORE8 | CALLDATACOPY | CODECOPY | RETURNDATACOPY | MCOPY => (3, true),
+        PUSH1..=PUSH32 | DUP1..=DUP16 | SWAP1..=SWAP16 => (3, false),
+        MUL | DIV | SDIV | MOD | SMOD | SIGNEXTEND | SELFBALANCE => (5, false),
+        ADDMOD | MULMOD | JUMP => (8, false),
+        JUMPI => (10, false),
+        EXP => (10, true),
+        BLOCKHASH => (20, false),
+        KECCAK256 => (30, true),
+        TLOAD | TSTORE => (100, false),
+        BALANCE | EXTCODESIZE | EXTCODEHASH | EXTCODECOPY | SLOAD | SSTORE | CALL | CALLCODE
+        | DELEGATECALL | STATICCALL => (100, true),
+        LOG0..=LOG4 => (375 * (1 + (op - LOG0) as u64), true),
+        SELFDESTRUCT => (5000, true),
+        CREATE | CREATE2 => (32000, true),
+        _ => return None,
+    };
+    Some(gas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_finds_blocks_and_selectors() {
+        // PUSH1 0 CALLDATALOAD PUSH1 0xe0 SHR DUP1 PUSH4 0x60fe47b1 EQ PUSH1 0x13 JUMPI
+        // PUSH0 DUP1 REVERT JUMPDEST STOP
+        let code = hex::decode("60003560e01c806360fe47b1146013575f80fd5b00").unwrap();
+        let disassembly = disassemble(&code);
+
+        assert_eq!(disassembly.instructions[5].name, "PUSH4");
+        assert_eq!(disassembly.selectors.len(), 1);
+        assert_eq!(
+            disassembly.selectors[0].selector,
+            Selector::from([0x60, 0xfe, 0x47, 0xb1])
+        );
+        assert_eq!(disassembly.selectors[0].target, Some(0x13));
+        assert_eq!(disassembly.jump_destinations, vec![0x13]);
+        let starts: Vec<_> = disassembly
+            .basic_blocks
+            .iter()
+            .map(|block| block.start)
+            .collect();
+        assert_eq!(starts, vec![0, 0x10, 0x13]);
+    }
+
+    #[test]
+    fn test_keeps_trailing_bytes_that_are_not_metadata() {
+        // Ends like metadata (a CBOR map and its length), but names no compiler,
+        // e.g. constructor arguments at the end of initcode
+        let code = [0x60, 0x00, 0xa1, 0x01, 0x02, 0x00, 0x03];
+        let disassembly = disassemble(&code);
+
+        assert!(disassembly.metadata.is_none());
+        assert_eq!(disassembly.instructions.last().unwrap().pc, 6);
+    }
+
+    #[test]
+    fn test_strips_vyper_array_metadata() {
+        // PUSH1 0, then vyper 0.3.10's [291, [], 0, {"vyper": [0, 3, 10]}]
+        let code = hex::decode("6000841901238000a16576797065728300030a0013").unwrap();
+        let disassembly = disassemble(&code);
+
+        assert_eq!(disassembly.metadata.unwrap().len(), 19);
+        assert_eq!(disassembly.instructions.len(), 1);
+    }
+}
@@ -0,0 +1,145 @@
+use alloy_primitives::Bytes;
+use ciborium::Value;
+use serde::Serialize;
+
+/// The compiler metadata appended to runtime code.
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    /// IPFS multihash of the metadata JSON.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipfs: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bzzr0: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bzzr1: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vyper: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experimental: Option<bool>,
+}
+
+/// Decodes metadata as split off by `split_metadata`, length suffix included.
+/// solc appends a CBOR map, vyper an array that ends with one.
+pub fn decode_metadata(metadata: &[u8]) -> Result<Metadata, eyre::Error> {
+    let cbor = &metadata[..metadata.len().saturating_sub(2)];
+    let value: Value =
+        ciborium::from_reader(cbor).map_err(|err| eyre::eyre!("Invalid CBOR metadata: {}", err))?;
+
+    let entries = match value {
+        Value::Map(entries) => entries,
+        Value::Array(items) => items
+            .into_iter()
+            .find_map(|item| match item {
+                Value::Map(entries) => Some(entries),
+                _ => None,
+            })
+            .unwrap_or_default(),
+        _ => return Err(eyre::eyre!("CBOR metadata is not a map")),
+    };
+
+    let mut decoded = Metadata::default();
+    for (key, value) in entries {
+        let Value::Text(key) = key else { continue };
+        match (key.as_str(), value) {
+            ("ipfs", Value::Bytes(hash)) => decoded.ipfs = Some(hash.into()),
+            ("bzzr0", Value::Bytes(hash)) => decoded.bzzr0 = Some(hash.into()),
+            ("bzzr1", Value::Bytes(hash)) => decoded.bzzr1 = Some(hash.into()),
+            ("solc", version) => decoded.solc = version_string(version),
+            ("vyper", version) => decoded.vyper = version_string(version),
+            ("experimental", Value::Bool(experimental)) => {
+                decoded.experimental = Some(experimental)
+            }
+            _ => {}
+        }
+    }
+    Ok(decoded)
+}
+
+/// Release builds encode their version as bytes (`[0, 8, 26]`) or, for vyper,
+/// an array; prereleases as a string.
+fn version_string(value: Value) -> Option<String> {
+    let parts: Vec<String> = match value {
+        Value::Text(version) => return Some(version),
+        Value::Bytes(parts) => parts.iter().map(ToString::to_string).collect(),
+        Value::Array(parts) => parts
+            .into_iter()
+            .map(|part| Some(u64::try_from(part.as_integer()?).ok()?.to_string()))
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    Some(parts.join("."))
+}
+
+/// Splits solc/vyper's trailing CBOR metadata off `code`. The last two bytes
+/// hold the metadata's length. solc and vyper before 0.3.10 append a CBOR map
+/// and don't count the length bytes; vyper from 0.3.10 on appends a CBOR array
+/// and does.
+pub fn split_metadata(code: &[u8]) -> (&[u8], Option<&[u8]>) {
+    let Some(len_bytes) = code.len().checked_sub(2).map(|at| &code[at..]) else {
+        return (code, None);
+    };
+    let len = u16::from_be_bytes([len_bytes[0], len_bytes[1]]) as usize;
+
+    // 0xa0..=0xb7 are CBOR maps with up to 23 entries
+    let map_start = code
+        .len()
+        .checked_sub(len + 2)
+        .filter(|start| len > 0 && (0xa0..=0xb7).contains(&code[*start]));
+    // 0x80..=0x97 are CBOR arrays with up to 23 items
+    let array_start = code
+        .len()
+        .checked_sub(len)
+        .filter(|start| len > 2 && (0x80..=0x97).contains(&code[*start]));
+
+    match map_start.or(array_start) {
+        Some(start) => (&code[..start], Some(&code[start..])),
+        None => (code, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decodes_solc_metadata() {
+        // {"ipfs": h'1220..', "solc": h'00081a'} from a 0.8.26 build
+        let code = alloy_primitives::hex::decode(
+            "6080a2646970667358221220f7399e877793618afbf93c1ab591511f69fa1330a3fd5526ff45418127a04af964736f6c634300081a0033",
+        )
+        .unwrap();
+        let (code, metadata) = split_metadata(&code);
+        assert_eq!(code, [0x60, 0x80]);
+
+        let metadata = decode_metadata(metadata.unwrap()).unwrap();
+        assert_eq!(metadata.solc.as_deref(), Some("0.8.26"));
+        assert_eq!(metadata.ipfs.unwrap().len(), 34);
+    }
+
+    #[test]
+    fn test_splits_trailing_metadata() {
+        let code = [0x60, 0x00, 0xa1, 0x01, 0x02, 0x00, 0x03];
+        let (code, metadata) = split_metadata(&code);
+        assert_eq!(code, [0x60, 0x00]);
+        assert_eq!(metadata, Some(&[0xa1, 0x01, 0x02, 0x00, 0x03][..]));
+
+        assert_eq!(split_metadata(&[0x60, 0x00]).1, None);
+    }
+
+    #[test]
+    fn test_decodes_vyper_array_metadata() {
+        // [291, [], 0, {"vyper": [0, 3, 10]}] and its length, 19, counting
+        // the two length bytes
+        let code =
+            alloy_primitives::hex::decode("6000841901238000a16576797065728300030a0013").unwrap();
+        let (code, metadata) = split_metadata(&code);
+        assert_eq!(code, [0x60, 0x00]);
+
+        let metadata = decode_metadata(metadata.unwrap()).unwrap();
+        assert_eq!(metadata.vyper.as_deref(), Some("0.3.10"));
+        assert_eq!(metadata.solc, None);
+    }
+}
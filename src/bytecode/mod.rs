@@ -0,0 +1,10 @@
+mod assembler;
+pub use assembler::{assemble, Assembled, INITCODE_PREFIX_LEN};
+mod disassembler;
+pub use disassembler::{disassemble, BasicBlock, Disassembly, DispatcherEntry, Instruction};
+mod metadata;
+pub use metadata::{decode_metadata, split_metadata, Metadata};
+mod verify;
+pub use verify::{
+    compare_immutables, verify_runtime, ByteDiff, ImmutableValue, MatchType, Verification,
+};
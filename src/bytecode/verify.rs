@@ -0,0 +1,162 @@
+use alloy_primitives::Bytes;
+use serde::Serialize;
+
+use super::{decode_metadata, split_metadata, Metadata};
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchType {
+    /// Identical, metadata included.
+    Exact,
+    /// Identical code, but the metadata differs, e.g. from different source
+    /// comments or paths.
+    Partial,
+    Mismatch,
+}
+
+/// A run of differing bytes, with offsets into the code without metadata.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteDiff {
+    pub offset: usize,
+    pub expected: Bytes,
+    pub actual: Bytes,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+    pub match_type: MatchType,
+    pub expected_metadata: Option<Metadata>,
+    pub actual_metadata: Option<Metadata>,
+    pub expected_length: usize,
+    pub actual_length: usize,
+    pub diffs: Vec<ByteDiff>,
+}
+
+/// The value of one immutable, as filled in by running the constructor here
+/// and as deployed.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImmutableValue {
+    pub offset: usize,
+    pub expected: Bytes,
+    pub actual: Bytes,
+    pub matches: bool,
+}
+
+/// Compares the immutables in `ranges` of two runtime codes.
+pub fn compare_immutables(
+    expected: &[u8],
+    actual: &[u8],
+    ranges: &[(usize, usize)],
+) -> Vec<ImmutableValue> {
+    ranges
+        .iter()
+        .map(|(start, length)| {
+            let range = *start..start + length;
+            let expected = expected.get(range.clone()).unwrap_or_default();
+            let actual = actual.get(range).unwrap_or_default();
+            ImmutableValue {
+                offset: *start,
+                expected: Bytes::copy_from_slice(expected),
+                actual: Bytes::copy_from_slice(actual),
+                matches: expected == actual,
+            }
+        })
+        .collect()
+}
+
+/// Compares runtime code, ignoring the bytes in `masked` ranges of `actual`
+/// (immutables that `expected` has as zeros).
+pub fn verify_runtime(expected: &[u8], actual: &[u8], masked: &[(usize, usize)]) -> Verification {
+    let mut actual = actual.to_vec();
+    for (start, length) in masked {
+        if let (Some(range), Some(zeros)) = (
+            actual.get_mut(*start..start + length),
+            expected.get(*start..start + length),
+        ) {
+            range.copy_from_slice(zeros);
+        }
+    }
+
+    let (expected_code, expected_metadata) = split_metadata(expected);
+    let (actual_code, actual_metadata) = split_metadata(&actual);
+    let diffs = diff(expected_code, actual_code);
+
+    let match_type = if !diffs.is_empty() || expected_code.len() != actual_code.len() {
+        MatchType::Mismatch
+    } else if expected_metadata == actual_metadata {
+        MatchType::Exact
+    } else {
+        MatchType::Partial
+    };
+
+    Verification {
+        match_type,
+        expected_metadata: expected_metadata.and_then(|metadata| decode_metadata(metadata).ok()),
+        actual_metadata: actual_metadata.and_then(|metadata| decode_metadata(metadata).ok()),
+        expected_length: expected.len(),
+        actual_length: actual.len(),
+        diffs,
+    }
+}
+
+fn diff(expected: &[u8], actual: &[u8]) -> Vec<ByteDiff> {
+    let mut diffs: Vec<ByteDiff> = vec![];
+    let mut start = None;
+    for i in 0..expected.len().min(actual.len()) + 1 {
+        let differs = i < expected.len().min(actual.len()) && expected[i] != actual[i];
+        match (differs, start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                diffs.push(ByteDiff {
+                    offset: from,
+                    expected: Bytes::copy_from_slice(&expected[from..i]),
+                    actual: Bytes::copy_from_slice(&actual[from..i]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA_A: [u8; 6] = [0xa1, 0x61, 0x61, 0x01, 0x00, 0x04];
+    const METADATA_B: [u8; 6] = [0xa1, 0x61, 0x61, 0x02, 0x00, 0x04];
+
+    #[test]
+    fn test_classifies_matches() {
+        let expected = [[0x60, 0x00, 0x00].as_slice(), &METADATA_A].concat();
+        let exact = verify_runtime(&expected, &expected, &[]);
+        assert_eq!(exact.match_type, MatchType::Exact);
+
+        let other_metadata = [[0x60, 0x00, 0x00].as_slice(), &METADATA_B].concat();
+        let partial = verify_runtime(&expected, &other_metadata, &[]);
+        assert_eq!(partial.match_type, MatchType::Partial);
+
+        let other_code = [[0x60, 0x01, 0x00].as_slice(), &METADATA_A].concat();
+        let mismatch = verify_runtime(&expected, &other_code, &[]);
+        assert_eq!(mismatch.match_type, MatchType::Mismatch);
+        assert_eq!(mismatch.diffs[0].offset, 1);
+
+        let masked = verify_runtime(&expected, &other_code, &[(1, 1)]);
+        assert_eq!(masked.match_type, MatchType::Exact);
+    }
+
+    #[test]
+    fn test_compares_immutables() {
+        let expected = [0x60, 0xaa, 0x60, 0xbb, 0x00];
+        let actual = [0x60, 0xaa, 0x60, 0xcc, 0x00];
+        let values = compare_immutables(&expected, &actual, &[(1, 1), (3, 1)]);
+
+        assert!(values[0].matches);
+        assert!(!values[1].matches);
+        assert_eq!(values[1].actual, Bytes::from(vec![0xcc]));
+    }
+}
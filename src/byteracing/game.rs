@@ -5,7 +5,7 @@ use revm::{db::CacheDB, InMemoryDB};
 use revm_primitives::{Address, ExecutionResult};
 use serde::Serialize;
 
-use crate::gas::{deploy, transact};
+use crate::gas::{deploy, transact, ExecutionEnv, TraceOptions};
 
 #[derive(Clone, Debug)]
 pub struct Game {
@@ -66,7 +66,7 @@ sol! {
 impl Game {
     pub fn new(map: Map, car: Bytes, start_position: Position) -> Result<Self, eyre::Error> {
         let mut db = CacheDB::new(InMemoryDB::default());
-        let car_address = deploy(car, &mut db)?;
+        let car_address = deploy(car, &ExecutionEnv::default(), &mut db)?.address;
         Ok(Self {
             map,
             cur_position: start_position,
@@ -115,13 +115,11 @@ impl Game {
                 self.outcome = Some(RaceOutcome::Revert);
             }
             ExecutionResult::Success {
-                gas_used,
-                gas_refunded,
-                output,
-                ..
+                gas_used, output, ..
             } => {
                 println!("result success");
-                self.gas_used += gas_used - gas_refunded;
+                // revm's gas_used is already net of the refund
+                self.gas_used += gas_used;
                 if self.gas_used > 2_000_000 {
                     self.outcome = Some(RaceOutcome::MaxGas);
                     self.message = Some("Max gas 2M".to_string());
@@ -152,9 +150,11 @@ impl Game {
             Some(calldata.into()),
             None,
             None,
+            &ExecutionEnv::default(),
+            &TraceOptions::default(),
             &mut self.db,
         )?;
-        Ok(result)
+        Ok(result.result)
     }
 
     pub fn update_position(&mut self, r#move: Move) -> Result<(), eyre::Error> {
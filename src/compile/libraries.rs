@@ -0,0 +1,210 @@
+use foundry_compilers::artifacts::remappings::Remapping;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, env, fs, path::PathBuf};
+
+/// Where vendored libraries are looked up when `LIBRARIES_DIR` isn't set.
+const DEFAULT_LIBRARIES_DIR: &str = "/usr/local/lib/solidity-libs";
+
+/// A library we know how to remap: imports starting with `prefix` resolve to
+/// `sources` inside the library's checkout.
+struct KnownLibrary {
+    name: &'static str,
+    prefix: &'static str,
+    sources: &'static str,
+}
+
+const KNOWN_LIBRARIES: &[KnownLibrary] = &[
+    KnownLibrary {
+        name: "openzeppelin-contracts",
+        prefix: "@openzeppelin/contracts/",
+        sources: "contracts/",
+    },
+    KnownLibrary {
+        name: "openzeppelin-contracts-upgradeable",
+        prefix: "@openzeppelin/contracts-upgradeable/",
+        sources: "contracts/",
+    },
+    KnownLibrary {
+        name: "solmate",
+        prefix: "solmate/",
+        sources: "src/",
+    },
+    KnownLibrary {
+        name: "solady",
+        prefix: "solady/",
+        sources: "src/",
+    },
+    KnownLibrary {
+        name: "forge-std",
+        prefix: "forge-std/",
+        sources: "src/",
+    },
+];
+
+/// A library to compile against, defaulting to its newest vendored version.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct LibraryRequest {
+    pub name: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ResolvedLibrary {
+    pub name: String,
+    pub version: String,
+    pub root: PathBuf,
+    pub remapping: Remapping,
+}
+
+/// Vendored libraries checked out as `<dir>/<name>/<version>`.
+pub struct LibraryStore {
+    dir: PathBuf,
+}
+
+impl LibraryStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(env::var("LIBRARIES_DIR").unwrap_or_else(|_| DEFAULT_LIBRARIES_DIR.to_string()))
+    }
+
+    /// Vendored versions of every known library, oldest first.
+    pub fn available(&self) -> Result<BTreeMap<String, Vec<String>>, eyre::Error> {
+        let mut available = BTreeMap::new();
+        for library in KNOWN_LIBRARIES {
+            let versions = self.versions(library.name)?;
+            if !versions.is_empty() {
+                available.insert(library.name.to_string(), versions);
+            }
+        }
+        Ok(available)
+    }
+
+    pub fn resolve(
+        &self,
+        requests: &[LibraryRequest],
+    ) -> Result<Vec<ResolvedLibrary>, eyre::Error> {
+        // Two versions of one library would map the same import prefix twice
+        for (i, request) in requests.iter().enumerate() {
+            if requests[..i].iter().any(|other| other.name == request.name) {
+                return Err(eyre::eyre!(
+                    "Library {} is requested more than once",
+                    request.name
+                ));
+            }
+        }
+
+        requests
+            .iter()
+            .map(|request| {
+                let library = KNOWN_LIBRARIES
+                    .iter()
+                    .find(|library| library.name == request.name)
+                    .ok_or_else(|| eyre::eyre!("Unknown library {}", request.name))?;
+                let versions = self.versions(library.name)?;
+                let version = match &request.version {
+                    Some(version) if versions.contains(version) => version.clone(),
+                    Some(version) => {
+                        return Err(eyre::eyre!(
+                            "{} {} is not available (available: {})",
+                            library.name,
+                            version,
+                            versions.join(", ")
+                        ))
+                    }
+                    None => versions
+                        .last()
+                        .cloned()
+                        .ok_or_else(|| eyre::eyre!("{} is not available", library.name))?,
+                };
+
+                let root = self.dir.join(library.name).join(&version);
+                let remapping = Remapping {
+                    context: None,
+                    name: library.prefix.to_string(),
+                    path: root.join(library.sources).display().to_string(),
+                };
+                Ok(ResolvedLibrary {
+                    name: library.name.to_string(),
+                    version,
+                    root,
+                    remapping,
+                })
+            })
+            .collect()
+    }
+
+    fn versions(&self, name: &str) -> Result<Vec<String>, eyre::Error> {
+        let entries = match fs::read_dir(self.dir.join(name)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+        let mut versions = vec![];
+        for entry in entries {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                if let Some(version) = entry.file_name().to_str() {
+                    versions.push(version.to_string());
+                }
+            }
+        }
+        versions.sort_by(|a, b| compare_versions(a, b));
+        Ok(versions)
+    }
+}
+
+/// Orders anything that isn't a semver version (with or without a `v`), like a
+/// branch name, before every release, so it's never picked as the newest.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |version: &str| Version::parse(version.trim_start_matches('v')).ok();
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolves_newest_version_with_remapping() {
+        let dir = tempfile::tempdir().unwrap();
+        for version in ["4.9.6", "5.0.2", "5.0.10", "master"] {
+            fs::create_dir_all(dir.path().join("openzeppelin-contracts").join(version)).unwrap();
+        }
+        let store = LibraryStore::new(dir.path());
+
+        let resolved = store
+            .resolve(&[LibraryRequest {
+                name: "openzeppelin-contracts".to_string(),
+                version: None,
+            }])
+            .unwrap();
+        assert_eq!(resolved[0].version, "5.0.10");
+        assert_eq!(resolved[0].remapping.name, "@openzeppelin/contracts/");
+        assert!(resolved[0]
+            .remapping
+            .path
+            .ends_with("openzeppelin-contracts/5.0.10/contracts/"));
+
+        assert!(store
+            .resolve(&[LibraryRequest {
+                name: "solmate".to_string(),
+                version: None,
+            }])
+            .is_err());
+
+        let twice = LibraryRequest {
+            name: "openzeppelin-contracts".to_string(),
+            version: Some("4.9.6".to_string()),
+        };
+        assert!(store.resolve(&[twice.clone(), twice]).is_err());
+    }
+}
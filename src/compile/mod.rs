@@ -1 +1,6 @@
+pub mod libraries;
+pub mod settings;
+pub mod solc_store;
 pub mod solidity;
+pub mod vyper;
+pub mod yul;
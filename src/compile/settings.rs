@@ -0,0 +1,299 @@
+use foundry_compilers::{
+    artifacts::{
+        output_selection::{ContractOutputSelection, EvmOutputSelection},
+        vyper::VyperSettings,
+        BytecodeHash, DebuggingSettings, EvmVersion, RevertStrings, Settings, SettingsMetadata,
+    },
+    multi::MultiCompilerSettings,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::libraries::LibraryRequest;
+
+/// The compiler settings a request can change. Anything left out keeps solc's
+/// default.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct CompileSettings {
+    /// Vendored libraries to make importable.
+    #[serde(default)]
+    pub libraries: Vec<LibraryRequest>,
+    /// solc version to compile every file with, instead of resolving one from
+    /// each file's pragma.
+    pub solc_version: Option<Version>,
+    pub optimizer: Option<bool>,
+    pub optimizer_runs: Option<usize>,
+    pub via_ir: Option<bool>,
+    pub evm_version: Option<EvmVersion>,
+    pub bytecode_hash: Option<BytecodeHash>,
+    pub revert_strings: Option<RevertStrings>,
+    /// Outputs to request from solc on top of the ABI and bytecode.
+    #[serde(default)]
+    pub outputs: Vec<CompileOutput>,
+}
+
+/// An optional solc output. Everything but the AST ends up on each contract;
+/// ASTs are returned per source file.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompileOutput {
+    Ast,
+    StorageLayout,
+    MethodIdentifiers,
+    Devdoc,
+    Userdoc,
+    IrOptimized,
+    Assembly,
+    GasEstimates,
+}
+
+impl CompileOutput {
+    fn selection(self) -> Option<ContractOutputSelection> {
+        Some(match self {
+            Self::Ast => return None,
+            Self::StorageLayout => ContractOutputSelection::StorageLayout,
+            Self::MethodIdentifiers => EvmOutputSelection::MethodIdentifiers.into(),
+            Self::Devdoc => ContractOutputSelection::DevDoc,
+            Self::Userdoc => ContractOutputSelection::UserDoc,
+            Self::IrOptimized => ContractOutputSelection::IrOptimized,
+            Self::Assembly => EvmOutputSelection::Assembly.into(),
+            Self::GasEstimates => EvmOutputSelection::GasEstimates.into(),
+        })
+    }
+}
+
+impl CompileSettings {
+    pub fn solc_settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        if let Some(enabled) = self.optimizer {
+            settings.optimizer.enabled = Some(enabled);
+        }
+        if let Some(runs) = self.optimizer_runs {
+            settings.optimizer.runs = Some(runs);
+        }
+        if let Some(via_ir) = self.via_ir {
+            settings.via_ir = Some(via_ir);
+        }
+        if let Some(evm_version) = self.evm_version {
+            settings.evm_version = Some(evm_version);
+        }
+        if let Some(bytecode_hash) = self.bytecode_hash {
+            settings.metadata = Some(SettingsMetadata {
+                bytecode_hash: Some(bytecode_hash),
+                ..settings.metadata.unwrap_or_default()
+            });
+        }
+        if let Some(revert_strings) = self.revert_strings {
+            settings.debug = Some(DebuggingSettings {
+                revert_strings: Some(revert_strings),
+                ..settings.debug.unwrap_or_default()
+            });
+        }
+        settings =
+            settings.with_extra_output(self.outputs.iter().filter_map(|output| output.selection()));
+        if self.outputs.contains(&CompileOutput::Ast) {
+            settings = settings.with_ast();
+        }
+        settings
+    }
+
+    /// Vyper only takes the EVM version; see `check_vyper`.
+    pub fn vyper_settings(&self) -> VyperSettings {
+        VyperSettings {
+            evm_version: self.evm_version,
+            ..Default::default()
+        }
+    }
+
+    /// Rejects settings only solc understands, rather than silently compiling
+    /// Vyper sources without them.
+    pub fn check_vyper(&self) -> Result<(), eyre::Error> {
+        let unsupported = [
+            ("optimizer", self.optimizer.is_some()),
+            ("optimizer_runs", self.optimizer_runs.is_some()),
+            ("via_ir", self.via_ir.is_some()),
+            ("bytecode_hash", self.bytecode_hash.is_some()),
+            ("revert_strings", self.revert_strings.is_some()),
+            ("outputs", !self.outputs.is_empty()),
+        ];
+        let unsupported: Vec<_> = unsupported
+            .iter()
+            .filter(|(_, set)| *set)
+            .map(|(name, _)| *name)
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(eyre::eyre!(
+                "Settings {} are not supported for Vyper sources",
+                unsupported.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    /// Rejects settings `solc --strict-assembly` has no flag for, and optimizer
+    /// runs without the optimizer, which solc would ignore.
+    pub fn check_yul(&self) -> Result<(), eyre::Error> {
+        let unsupported = [
+            ("libraries", !self.libraries.is_empty()),
+            ("via_ir", self.via_ir.is_some()),
+            ("bytecode_hash", self.bytecode_hash.is_some()),
+            ("revert_strings", self.revert_strings.is_some()),
+            ("outputs", !self.outputs.is_empty()),
+        ];
+        let unsupported: Vec<_> = unsupported
+            .iter()
+            .filter(|(_, set)| *set)
+            .map(|(name, _)| *name)
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(eyre::eyre!(
+                "Settings {} are not supported for Yul",
+                unsupported.join(", ")
+            ));
+        }
+        if self.optimizer_runs.is_some() && self.optimizer != Some(true) {
+            return Err(eyre::eyre!("optimizer_runs needs optimizer enabled"));
+        }
+        Ok(())
+    }
+
+    pub fn project_settings(&self) -> MultiCompilerSettings {
+        MultiCompilerSettings {
+            solc: self.solc_settings(),
+            vyper: self.vyper_settings(),
+        }
+    }
+
+    /// The settings solc `version` is actually given, after dropping or
+    /// downgrading whatever it doesn't support, with solc's defaults filled in.
+    pub fn effective(&self, version: &Version) -> Self {
+        let settings = self.solc_settings().sanitized(version);
+        Self {
+            libraries: self.libraries.clone(),
+            solc_version: Some(version.clone()),
+            optimizer: Some(settings.optimizer.enabled.unwrap_or(false)),
+            optimizer_runs: Some(settings.optimizer.runs.unwrap_or(200)),
+            via_ir: Some(settings.via_ir.unwrap_or(false)),
+            evm_version: settings.evm_version,
+            bytecode_hash: Some(
+                settings
+                    .metadata
+                    .and_then(|metadata| metadata.bytecode_hash)
+                    .unwrap_or_default(),
+            ),
+            revert_strings: Some(
+                settings
+                    .debug
+                    .and_then(|debug| debug.revert_strings)
+                    .unwrap_or_default(),
+            ),
+            outputs: self.outputs.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fills_in_defaults() {
+        let settings = CompileSettings {
+            optimizer: Some(true),
+            via_ir: Some(true),
+            ..Default::default()
+        };
+        let solc = settings.solc_settings();
+        assert_eq!(solc.optimizer.enabled, Some(true));
+        assert_eq!(solc.via_ir, Some(true));
+
+        let effective = settings.effective(&Version::new(0, 8, 26));
+        assert_eq!(effective.solc_version, Some(Version::new(0, 8, 26)));
+        assert_eq!(effective.optimizer_runs, Some(200));
+        assert_eq!(effective.bytecode_hash, Some(BytecodeHash::Ipfs));
+    }
+
+    #[test]
+    fn test_vyper_rejects_solc_only_settings() {
+        let settings = CompileSettings {
+            evm_version: Some(EvmVersion::Paris),
+            ..Default::default()
+        };
+        assert!(settings.check_vyper().is_ok());
+        assert_eq!(
+            settings.vyper_settings().evm_version,
+            Some(EvmVersion::Paris)
+        );
+
+        let settings = CompileSettings {
+            optimizer: Some(true),
+            via_ir: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.check_vyper().unwrap_err().to_string(),
+            "Settings optimizer, via_ir are not supported for Vyper sources"
+        );
+    }
+
+    #[test]
+    fn test_yul_rejects_unsupported_settings() {
+        let settings = CompileSettings {
+            optimizer: Some(true),
+            optimizer_runs: Some(1),
+            evm_version: Some(EvmVersion::Paris),
+            ..Default::default()
+        };
+        assert!(settings.check_yul().is_ok());
+
+        let settings = CompileSettings {
+            via_ir: Some(true),
+            outputs: vec![CompileOutput::Assembly],
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.check_yul().unwrap_err().to_string(),
+            "Settings via_ir, outputs are not supported for Yul"
+        );
+
+        let settings = CompileSettings {
+            optimizer_runs: Some(1),
+            ..Default::default()
+        };
+        assert!(settings.check_yul().is_err());
+    }
+
+    #[test]
+    fn test_effective_evm_version_is_normalized() {
+        let settings = CompileSettings {
+            evm_version: Some(EvmVersion::Cancun),
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.effective(&Version::new(0, 8, 2)).evm_version,
+            Some(EvmVersion::Istanbul)
+        );
+        assert_eq!(
+            settings.effective(&Version::new(0, 8, 26)).evm_version,
+            Some(EvmVersion::Cancun)
+        );
+    }
+
+    #[test]
+    fn test_selects_extra_outputs() {
+        let settings = CompileSettings {
+            outputs: vec![
+                CompileOutput::StorageLayout,
+                CompileOutput::Assembly,
+                CompileOutput::Ast,
+            ],
+            ..Default::default()
+        };
+        let selection = serde_json::to_value(settings.solc_settings().output_selection).unwrap();
+        let contract = selection["*"]["*"].as_array().unwrap();
+        assert!(contract.contains(&"storageLayout".into()));
+        assert!(contract.contains(&"evm.assembly".into()));
+        assert!(contract.contains(&"abi".into()));
+        assert_eq!(selection["*"][""], serde_json::json!(["ast"]));
+    }
+}
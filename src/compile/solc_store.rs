@@ -0,0 +1,366 @@
+use foundry_compilers::solc::Solc;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::PathBuf,
+    sync::OnceLock,
+};
+
+use super::solidity::SolidityFile;
+
+/// Where solc binaries are looked up when `SOLC_DIR` isn't set.
+const DEFAULT_SOLC_DIR: &str = "/usr/local/lib/solc";
+
+/// A directory of pre-installed solc binaries named `solc-<version>`. Nothing
+/// is ever downloaded.
+pub struct SolcStore {
+    dir: PathBuf,
+}
+
+impl SolcStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(env::var("SOLC_DIR").unwrap_or_else(|_| DEFAULT_SOLC_DIR.to_string()))
+    }
+
+    /// Installed versions, oldest first. A missing directory has none.
+    pub fn installed(&self) -> Result<Vec<Version>, eyre::Error> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut versions = vec![];
+        for entry in entries {
+            let name = entry?.file_name();
+            if let Some(version) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("solc-"))
+                .and_then(|version| Version::parse(version).ok())
+            {
+                versions.push(version);
+            }
+        }
+        versions.sort();
+        Ok(versions)
+    }
+
+    pub fn solc(&self, version: &Version) -> Result<Solc, eyre::Error> {
+        Ok(Solc::new_with_version(self.path(version)?, version.clone()))
+    }
+
+    /// Path of the `requested` solc, or of the newest installed one. Falls back
+    /// to `solc` on PATH when the store is empty.
+    pub fn binary(&self, requested: Option<&Version>) -> Result<PathBuf, eyre::Error> {
+        match requested {
+            Some(version) => self.path(version),
+            None => match self.installed()?.last() {
+                Some(version) => self.path(version),
+                None => Ok(PathBuf::from("solc")),
+            },
+        }
+    }
+
+    fn path(&self, version: &Version) -> Result<PathBuf, eyre::Error> {
+        let path = self.dir.join(format!("solc-{}", version));
+        if !path.is_file() {
+            return Err(eyre::eyre!("solc {} is not installed", version));
+        }
+        Ok(path)
+    }
+}
+
+/// Converts a Solidity version pragma into semver requirements, any of which
+/// may match. Unlike semver, Solidity reads a bare version as an exact match
+/// and separates conditions with spaces.
+pub fn parse_pragma(pragma: &str) -> Result<Vec<VersionReq>, eyre::Error> {
+    static CONDITION: OnceLock<Regex> = OnceLock::new();
+    let condition = CONDITION
+        .get_or_init(|| Regex::new(r"(\^|~|>=|<=|>|<|=)?\s*(\d+(?:\.(?:\d+|[x*]))*)").unwrap());
+
+    pragma
+        .split("||")
+        .map(|alternative| {
+            let requirement = match alternative.split_once(" - ") {
+                Some((from, to)) => format!(">={}, <={}", from.trim(), to.trim()),
+                None => condition
+                    .captures_iter(alternative)
+                    .map(|caps| {
+                        let op = caps.get(1).map_or("=", |op| op.as_str());
+                        format!("{}{}", op, &caps[2])
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            VersionReq::parse(&requirement)
+                .map_err(|err| eyre::eyre!("Invalid pragma solidity {}: {}", pragma.trim(), err))
+        })
+        .collect()
+}
+
+/// Blanks out comments, so pragmas and imports in them aren't picked up.
+/// String literals are kept as they are, since import paths can contain `//`.
+fn strip_comments(content: &str) -> String {
+    let mut code = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(open) = quote {
+            code.push(c);
+            if c == '\\' {
+                code.extend(chars.next());
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"' | '\'', _) => {
+                quote = Some(c);
+                code.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|next| *next != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = None;
+                for next in chars.by_ref() {
+                    if prev == Some('*') && next == '/' {
+                        break;
+                    }
+                    prev = Some(next);
+                }
+                code.push(' ');
+            }
+            _ => code.push(c),
+        }
+    }
+    code
+}
+
+/// Every `pragma solidity` in a source file.
+fn pragmas(content: &str) -> Vec<&str> {
+    static PRAGMA: OnceLock<Regex> = OnceLock::new();
+    PRAGMA
+        .get_or_init(|| Regex::new(r"pragma\s+solidity\s+([^;]+);").unwrap())
+        .captures_iter(content)
+        .filter_map(|caps| Some(caps.get(1)?.as_str()))
+        .collect()
+}
+
+/// Paths of every import in a source file, as written.
+fn imports(content: &str) -> Vec<&str> {
+    static IMPORT: OnceLock<Regex> = OnceLock::new();
+    IMPORT
+        .get_or_init(|| Regex::new(r#"import\s+(?:[^;]*?\bfrom\s+)?["']([^"']+)["']"#).unwrap())
+        .captures_iter(content)
+        .filter_map(|caps| Some(caps.get(1)?.as_str()))
+        .collect()
+}
+
+/// Resolves `import` as written in the file `from` to a source name.
+fn resolve_import(from: &str, import: &str) -> String {
+    if !import.starts_with("./") && !import.starts_with("../") {
+        return import.to_string();
+    }
+    let mut parts: Vec<&str> = from.split('/').collect();
+    parts.pop();
+    for part in import.split('/') {
+        match part {
+            "." | "" => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Picks a solc version for every file and groups the files by it. Files that
+/// import each other, directly or through a shared library file, are compiled
+/// together since solc compiles a file's imports along with it; they get the
+/// newest installed version that satisfies all their pragmas, or `requested`
+/// if given.
+pub fn group_by_version(
+    files: &[&SolidityFile],
+    installed: &[Version],
+    requested: Option<&Version>,
+) -> Result<BTreeMap<Version, Vec<String>>, eyre::Error> {
+    if let Some(version) = requested {
+        if !installed.contains(version) {
+            return Err(eyre::eyre!("solc {} is not installed", version));
+        }
+        let names = files.iter().map(|file| file.name.clone()).collect();
+        return Ok(BTreeMap::from([(version.clone(), names)]));
+    }
+
+    let by_name: BTreeMap<&str, &SolidityFile> = files
+        .iter()
+        .map(|file| (file.name.as_str(), *file))
+        .collect();
+    // Imports that aren't among the request's files (libraries, typos) are
+    // still nodes, so files sharing them end up together.
+    let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for file in files {
+        for import in imports(&strip_comments(&file.content)) {
+            let name = resolve_import(&file.name, import);
+            edges
+                .entry(file.name.clone())
+                .or_default()
+                .push(name.clone());
+            edges.entry(name).or_default().push(file.name.clone());
+        }
+    }
+
+    let mut groups: BTreeMap<Version, Vec<String>> = BTreeMap::new();
+    let mut seen = BTreeSet::new();
+    for file in files {
+        if !seen.insert(file.name.clone()) {
+            continue;
+        }
+        let mut queue = vec![file.name.clone()];
+        let mut component = vec![];
+        while let Some(name) = queue.pop() {
+            if let Some(file) = by_name.get(name.as_str()) {
+                component.push(*file);
+            }
+            for next in edges.get(&name).into_iter().flatten() {
+                if seen.insert(next.clone()) {
+                    queue.push(next.clone());
+                }
+            }
+        }
+
+        let mut requirements = vec![];
+        for file in &component {
+            for pragma in pragmas(&strip_comments(&file.content)) {
+                requirements.push(parse_pragma(pragma)?);
+            }
+        }
+        let names: Vec<String> = component.iter().map(|file| file.name.clone()).collect();
+
+        let version = installed
+            .iter()
+            .rev()
+            .find(|version| {
+                requirements
+                    .iter()
+                    .all(|alternatives| alternatives.iter().any(|req| req.matches(version)))
+            })
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "No installed solc satisfies {} together (installed: {})",
+                    names.join(", "),
+                    installed
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            })?;
+        groups.entry(version.clone()).or_default().extend(names);
+    }
+
+    for names in groups.values_mut() {
+        names.sort();
+    }
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> SolidityFile {
+        SolidityFile {
+            name: name.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_converts_pragmas_to_semver() {
+        let exact = parse_pragma("0.8.2").unwrap();
+        assert!(exact[0].matches(&Version::new(0, 8, 2)));
+        assert!(!exact[0].matches(&Version::new(0, 8, 3)));
+
+        let range = parse_pragma(">=0.7.0 <0.9.0").unwrap();
+        assert!(range[0].matches(&Version::new(0, 8, 26)));
+        assert!(!range[0].matches(&Version::new(0, 9, 0)));
+
+        let either = parse_pragma("^0.6.0 || ^0.8.0").unwrap();
+        assert_eq!(either.len(), 2);
+        assert!(either[1].matches(&Version::new(0, 8, 26)));
+    }
+
+    #[test]
+    fn test_groups_files_with_their_imports() {
+        let installed = [
+            Version::new(0, 8, 2),
+            Version::new(0, 8, 19),
+            Version::new(0, 8, 26),
+        ];
+        let files = [
+            file(
+                "A.sol",
+                "pragma solidity 0.8.2;\nimport {B} from \"./lib/B.sol\";",
+            ),
+            file("lib/B.sol", "pragma solidity ^0.8.1;"),
+            file("C.sol", "pragma solidity >=0.8.0 <0.8.20;"),
+        ];
+
+        let files: Vec<_> = files.iter().collect();
+        let groups = group_by_version(&files, &installed, None).unwrap();
+        // B alone would get 0.8.26, but A needs it compiled with 0.8.2
+        assert_eq!(groups[&Version::new(0, 8, 2)], vec!["A.sol", "lib/B.sol"]);
+        assert_eq!(groups[&Version::new(0, 8, 19)], vec!["C.sol"]);
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn test_files_sharing_a_library_share_a_version() {
+        let installed = [Version::new(0, 8, 19), Version::new(0, 8, 26)];
+        let files = [
+            file(
+                "A.sol",
+                "pragma solidity ^0.8.0;\nimport \"solmate/tokens/ERC20.sol\";",
+            ),
+            file(
+                "B.sol",
+                "pragma solidity <0.8.20;\nimport \"solmate/tokens/ERC20.sol\";",
+            ),
+        ];
+
+        let files: Vec<_> = files.iter().collect();
+        let groups = group_by_version(&files, &installed, None).unwrap();
+        assert_eq!(groups[&Version::new(0, 8, 19)], vec!["A.sol", "B.sol"]);
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_ignores_pragmas_and_imports_in_comments() {
+        let installed = [Version::new(0, 7, 6), Version::new(0, 8, 26)];
+        let files = [
+            file(
+                "A.sol",
+                "// pragma solidity 0.7.6;\n\
+                 /* pragma solidity 0.7.6;\n import \"./C.sol\"; */\n\
+                 pragma solidity >=0.7.0; // import \"./C.sol\";\n\
+                 import \"./lib//B.sol\";",
+            ),
+            file("lib/B.sol", "pragma solidity >=0.7.0;"),
+            file("C.sol", "pragma solidity 0.7.6;"),
+        ];
+
+        let files: Vec<_> = files.iter().collect();
+        let groups = group_by_version(&files, &installed, None).unwrap();
+        assert_eq!(groups[&Version::new(0, 8, 26)], vec!["A.sol", "lib/B.sol"]);
+        assert_eq!(groups[&Version::new(0, 7, 6)], vec!["C.sol"]);
+    }
+}
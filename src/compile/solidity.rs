@@ -1,10 +1,32 @@
+use super::{
+    libraries::{LibraryRequest, LibraryStore, ResolvedLibrary},
+    settings::CompileSettings,
+    solc_store::{group_by_version, SolcStore},
+    vyper::{is_vyper, vyper},
+};
+use crate::abi::append_constructor_args;
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Bytes;
 use foundry_compilers::{
-    contracts::VersionedContracts, multi::MultiCompilerError, Project, ProjectPathsConfig,
+    artifacts::{Ast, Contract, DeployedBytecode},
+    compilers::CompilationError,
+    contracts::VersionedContracts,
+    multi::{MultiCompiler, MultiCompilerError},
+    solc::{Solc, SolcCompiler},
+    sources::VersionedSourceFiles,
+    AggregatedCompilerOutput, Project, ProjectPathsConfig,
 };
+use semver::Version;
 use serde::{Deserialize, Serialize};
-use std::fs;
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Component, Path, PathBuf},
+};
 use tempfile::{self, TempDir};
 
+/// A source file, Solidity or, with a `.vy`/`.vyi` name, Vyper.
 #[derive(Deserialize)]
 pub struct SolidityFile {
     pub name: String,
@@ -15,39 +37,364 @@ pub struct SolidityFile {
 pub struct CompileResult {
     pub errors: Vec<MultiCompilerError>,
     pub contracts: VersionedContracts,
+    /// ASTs by source file and the solc version that produced them, when
+    /// requested in the settings.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub asts: BTreeMap<PathBuf, BTreeMap<Version, Ast>>,
+    /// Kept to resolve the file indices in source maps.
+    #[serde(skip)]
+    pub sources: VersionedSourceFiles,
+    /// Settings each solc version was given, defaults included.
+    pub settings: BTreeMap<Version, CompileSettings>,
+    /// solc version each file was compiled with.
+    pub solc_versions: BTreeMap<String, Version>,
+    /// Directory the request's files were written to, which compiler output
+    /// paths are under.
+    #[serde(skip)]
+    pub sources_dir: PathBuf,
+}
+
+/// A compiled contract picked out of a `CompileResult`.
+pub struct FoundContract<'a> {
+    pub path: &'a Path,
+    pub version: &'a Version,
+    pub contract: &'a Contract,
+}
+
+impl FoundContract<'_> {
+    pub fn abi(&self) -> Option<&JsonAbi> {
+        self.contract.abi.as_ref()
+    }
+
+    pub fn initcode(&self) -> Result<&Bytes, eyre::Error> {
+        self.contract
+            .evm
+            .as_ref()
+            .and_then(|evm| evm.bytecode.as_ref()?.object.as_bytes())
+            .ok_or_else(|| eyre::eyre!("Contract has no bytecode, is it abstract or unlinked?"))
+    }
+
+    /// Initcode with `args` ABI-encoded for the constructor.
+    pub fn initcode_with_args(&self, args: Option<&[Value]>) -> Result<Bytes, eyre::Error> {
+        append_constructor_args(self.initcode()?.to_vec(), self.abi(), args)
+    }
+
+    /// Runtime code, without immutables filled in.
+    pub fn runtime(&self) -> Option<&Bytes> {
+        self.deployed_bytecode()?
+            .bytecode
+            .as_ref()?
+            .object
+            .as_bytes()
+    }
+
+    /// Runtime code and its source map.
+    pub fn runtime_source_map(&self) -> Option<(&Bytes, &str)> {
+        let bytecode = self.deployed_bytecode()?.bytecode.as_ref()?;
+        Some((bytecode.object.as_bytes()?, bytecode.source_map.as_deref()?))
+    }
+
+    /// `(start, length)` of every immutable in the runtime code.
+    pub fn immutable_ranges(&self) -> Vec<(usize, usize)> {
+        self.deployed_bytecode()
+            .map(|bytecode| {
+                bytecode
+                    .immutable_references
+                    .values()
+                    .flatten()
+                    .map(|offsets| (offsets.start as usize, offsets.length as usize))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn deployed_bytecode(&self) -> Option<&DeployedBytecode> {
+        self.contract.evm.as_ref()?.deployed_bytecode.as_ref()
+    }
+}
+
+impl CompileResult {
+    fn extend(&mut self, output: &AggregatedCompilerOutput<MultiCompiler>) {
+        self.errors.extend(output.errors.iter().cloned());
+        for (path, contracts) in output.contracts.0.iter() {
+            let entry = self.contracts.0.entry(path.clone()).or_default();
+            for (name, versions) in contracts {
+                entry
+                    .entry(name.clone())
+                    .or_default()
+                    .extend(versions.iter().cloned());
+            }
+        }
+        for (path, sources) in output.sources.0.iter() {
+            for source in sources {
+                if let Some(ast) = &source.source_file.ast {
+                    self.asts
+                        .entry(path.clone())
+                        .or_default()
+                        .insert(source.version.clone(), ast.clone());
+                }
+            }
+            self.sources
+                .0
+                .entry(path.clone())
+                .or_default()
+                .extend(sources.iter().cloned());
+        }
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors.iter().any(|err| err.is_error())
+    }
+
+    /// Finds a contract by name, or by `File.sol:Name` when the name alone is
+    /// ambiguous.
+    pub fn find_contract(&self, name: &str) -> Result<FoundContract<'_>, eyre::Error> {
+        let (file, name) = match name.rsplit_once(':') {
+            Some((file, name)) => (Some(virtual_path(file)?), name),
+            None => (None, name),
+        };
+
+        let mut found = self.contracts.0.iter().filter_map(|(path, contracts)| {
+            if file
+                .as_ref()
+                .is_some_and(|file| self.source_path(path) != Some(file.as_path()))
+            {
+                return None;
+            }
+            let versioned = contracts.get(name)?.first()?;
+            Some(FoundContract {
+                path: path.as_ref(),
+                version: &versioned.version,
+                contract: &versioned.contract,
+            })
+        });
+
+        let contract = found
+            .next()
+            .ok_or_else(|| eyre::eyre!("Contract {} not found in compiler output", name))?;
+        if found.next().is_some() {
+            return Err(eyre::eyre!(
+                "Contract name {} is ambiguous, use File.sol:{}",
+                name,
+                name
+            ));
+        }
+        Ok(contract)
+    }
+
+    /// Path of a compiled file relative to the sources directory, which is the
+    /// request's file name as normalized by `virtual_path`. `None` for files
+    /// from libraries.
+    pub fn source_path<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if path.is_relative() {
+            return Some(path);
+        }
+        path.strip_prefix(&self.sources_dir).ok()
+    }
+
+    /// Source ids of the files compiled with `version`, which is what the
+    /// source maps of that version's contracts refer to.
+    pub fn source_ids(&self, version: &Version) -> BTreeMap<u32, &Path> {
+        self.sources
+            .0
+            .iter()
+            .flat_map(|(path, sources)| {
+                sources
+                    .iter()
+                    .filter(|source| &source.version == version)
+                    .map(move |source| (source.source_file.id, path.as_ref()))
+            })
+            .collect()
+    }
 }
 
-pub fn compile(files: &[SolidityFile]) -> Result<CompileResult, eyre::Error> {
+pub fn compile(
+    files: &[SolidityFile],
+    settings: &CompileSettings,
+) -> Result<CompileResult, eyre::Error> {
     // Create a temporary directory
     let temp_dir = TempDir::new()?;
 
     // Create a subdirectory for sources
     let sources_dir = temp_dir.path().join("src");
     fs::create_dir(&sources_dir)?;
+    // The compiler reports canonical paths, compare against the same
+    let sources_dir = sources_dir.canonicalize()?;
 
-    // Write each Solidity file to the sources directory
+    // Write each file to the sources directory, under its normalized path
+    let mut normalized: Vec<SolidityFile> = Vec::with_capacity(files.len());
     for file in files {
-        let file_path = sources_dir.join(&file.name);
-        fs::write(&file_path, &file.content)?;
+        let path = virtual_path(&file.name)?;
+        let name = path.to_string_lossy().into_owned();
+        if normalized.iter().any(|other| other.name == name) {
+            return Err(eyre::eyre!("Duplicate source file {}", file.name));
+        }
+
+        let file_path = sources_dir.join(&path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|err| eyre::eyre!("Failed to write {}: {}", file.name, err))?;
+        }
+        fs::write(&file_path, &file.content)
+            .map_err(|err| eyre::eyre!("Failed to write {}: {}", file.name, err))?;
+        normalized.push(SolidityFile {
+            name,
+            content: file.content.clone(),
+        });
+    }
+    let files = &normalized;
+
+    let libraries = LibraryStore::from_env().resolve(&settings.libraries)?;
+    // Report the library versions actually used, not just the ones asked for
+    let settings = &CompileSettings {
+        libraries: libraries
+            .iter()
+            .map(|library| LibraryRequest {
+                name: library.name.clone(),
+                version: Some(library.version.clone()),
+            })
+            .collect(),
+        ..settings.clone()
+    };
+
+    let mut result = CompileResult {
+        errors: vec![],
+        contracts: Default::default(),
+        asts: BTreeMap::new(),
+        sources: Default::default(),
+        settings: BTreeMap::new(),
+        solc_versions: BTreeMap::new(),
+        sources_dir: sources_dir.clone(),
+    };
+
+    let (vyper_files, solidity_files): (Vec<_>, Vec<_>) =
+        files.iter().partition(|file| is_vyper(&file.name));
+
+    if !solidity_files.is_empty() {
+        compile_solidity(
+            &solidity_files,
+            &sources_dir,
+            settings,
+            &libraries,
+            &mut result,
+        )?;
+    }
+
+    if !vyper_files.is_empty() {
+        settings.check_vyper()?;
+        let compiler = MultiCompiler {
+            solc: None,
+            vyper: Some(vyper()?),
+        };
+        let output = project(&sources_dir, settings, &libraries, compiler)?
+            .compile_files(vyper_files.iter().map(|file| sources_dir.join(&file.name)))?;
+        result.extend(output.output());
+    }
+
+    Ok(result)
+}
+
+/// Normalizes a source file name into a relative path inside the sources
+/// directory, dropping `.` components and rejecting anything that could
+/// escape it.
+pub fn virtual_path(name: &str) -> Result<PathBuf, eyre::Error> {
+    let mut path = PathBuf::new();
+    for component in Path::new(name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                return Err(eyre::eyre!(
+                    "Invalid source path {}: `..` is not allowed",
+                    name
+                ))
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(eyre::eyre!(
+                    "Invalid source path {}: must be relative",
+                    name
+                ))
+            }
+        }
     }
+    if path.as_os_str().is_empty() {
+        return Err(eyre::eyre!("Invalid source path {}: empty file name", name));
+    }
+    Ok(path)
+}
+
+fn compile_solidity(
+    files: &[&SolidityFile],
+    sources_dir: &Path,
+    settings: &CompileSettings,
+    libraries: &[ResolvedLibrary],
+    result: &mut CompileResult,
+) -> Result<(), eyre::Error> {
+    let store = SolcStore::from_env();
+    let installed = store.installed()?;
+    let groups = if installed.is_empty() {
+        // No compiler store, fall back to the `solc` on PATH
+        let solc = Solc::new("solc")?;
+        if let Some(version) = &settings.solc_version {
+            if (version.major, version.minor, version.patch)
+                != (solc.version.major, solc.version.minor, solc.version.patch)
+            {
+                return Err(eyre::eyre!(
+                    "solc {} is not installed (solc on PATH is {})",
+                    version,
+                    solc.version
+                ));
+            }
+        }
+        let names = files.iter().map(|file| file.name.clone()).collect();
+        vec![(solc, names)]
+    } else {
+        group_by_version(files, &installed, settings.solc_version.as_ref())?
+            .into_iter()
+            .map(|(version, names)| Ok((store.solc(&version)?, names)))
+            .collect::<Result<Vec<_>, eyre::Error>>()?
+    };
+
+    for (solc, names) in groups {
+        let version = solc.version.clone();
+        let compiler = MultiCompiler {
+            solc: Some(SolcCompiler::Specific(solc)),
+            vyper: None,
+        };
+        let output = project(sources_dir, settings, libraries, compiler)?
+            .compile_files(names.iter().map(|name| sources_dir.join(name)))?;
+        result.extend(output.output());
+        result
+            .settings
+            .insert(version.clone(), settings.effective(&version));
+        result
+            .solc_versions
+            .extend(names.into_iter().map(|name| (name, version.clone())));
+    }
+
+    Ok(())
+}
 
+fn project(
+    sources_dir: &Path,
+    settings: &CompileSettings,
+    libraries: &[ResolvedLibrary],
+    compiler: MultiCompiler,
+) -> Result<Project, eyre::Error> {
     let paths = ProjectPathsConfig::builder()
-        .root(sources_dir.clone())
+        .root(sources_dir)
         .sources(sources_dir)
+        .libs(libraries.iter().map(|library| library.root.clone()))
+        .remappings(libraries.iter().map(|library| library.remapping.clone()))
         .build()?;
 
-    let project = Project::builder()
+    Ok(Project::builder()
         .paths(paths)
+        .settings(settings.project_settings())
+        .allowed_paths(libraries.iter().map(|library| library.root.clone()))
         .ephemeral()
         .no_artifacts()
-        .build(Default::default())?;
-
-    let output = project.compile()?;
-
-    Ok(CompileResult {
-        errors: output.output().errors.clone(),
-        contracts: output.output().contracts.clone(),
-    })
+        .build(compiler)?)
 }
 
 #[cfg(test)]
@@ -95,7 +442,7 @@ mod tests {
             },
         ];
 
-        let result = compile(&files);
+        let result = compile(&files, &CompileSettings::default());
 
         // assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
 
@@ -117,6 +464,86 @@ mod tests {
         println!("Compilation successful: {:?}", compile_result);
     }
 
+    #[test]
+    fn test_compile_solidity_and_vyper() {
+        let files = vec![
+            SolidityFile {
+                name: "Counter.sol".to_string(),
+                content: "pragma solidity ^0.8.0;\ncontract Counter { uint256 public count; }"
+                    .to_string(),
+            },
+            SolidityFile {
+                name: "Token.vy".to_string(),
+                content: "total: public(uint256)\n".to_string(),
+            },
+        ];
+
+        let result = compile(&files, &CompileSettings::default()).unwrap();
+        assert!(!result.has_errors(), "{:?}", result.errors);
+        assert!(result.find_contract("Counter").is_ok());
+        assert!(result.find_contract("Token").is_ok());
+        assert!(result.find_contract("./Counter.sol:Counter").is_ok());
+        // Only the Solidity file went through solc
+        assert_eq!(
+            result.solc_versions.keys().collect::<Vec<_>>(),
+            ["Counter.sol"]
+        );
+
+        let settings = CompileSettings {
+            optimizer_runs: Some(1),
+            ..Default::default()
+        };
+        assert!(compile(&files, &settings).is_err());
+    }
+
+    #[test]
+    fn test_find_contract_by_exact_file() {
+        let file = |name: &str, value: u8| SolidityFile {
+            name: name.to_string(),
+            content: format!(
+                "pragma solidity ^0.8.0;\ncontract A {{ uint256 public value = {}; }}",
+                value
+            ),
+        };
+        let files = vec![
+            file("A.sol", 1),
+            file("lib/A.sol", 2),
+            file("./src/A.sol", 3),
+        ];
+        let result = compile(&files, &CompileSettings::default()).unwrap();
+        assert!(!result.has_errors(), "{:?}", result.errors);
+
+        assert!(result
+            .find_contract("A")
+            .unwrap_err()
+            .to_string()
+            .contains("ambiguous"));
+        for (name, path) in [
+            ("A.sol:A", "A.sol"),
+            ("./A.sol:A", "A.sol"),
+            ("lib/A.sol:A", "lib/A.sol"),
+            ("./src/A.sol:A", "src/A.sol"),
+        ] {
+            let contract = result.find_contract(name).unwrap();
+            assert_eq!(result.source_path(contract.path), Some(Path::new(path)));
+        }
+        assert!(result.find_contract("B.sol:A").is_err());
+    }
+
+    #[test]
+    fn test_virtual_paths_stay_inside_sources() {
+        assert_eq!(
+            virtual_path("./lib/../Foo.sol").unwrap_err().to_string(),
+            "Invalid source path ./lib/../Foo.sol: `..` is not allowed"
+        );
+        assert!(virtual_path("/etc/passwd").is_err());
+        assert!(virtual_path("./").is_err());
+        assert_eq!(
+            virtual_path("./src//lib/Foo.sol").unwrap(),
+            PathBuf::from("src/lib/Foo.sol")
+        );
+    }
+
     // #[test]
     // fn test_compile_invalid_contract() {
     //     let invalid_solidity_code = r#"
@@ -0,0 +1,28 @@
+use foundry_compilers::vyper::Vyper;
+use std::env;
+
+/// vyper binary used when `VYPER` isn't set.
+const DEFAULT_VYPER: &str = "vyper";
+
+pub fn is_vyper(name: &str) -> bool {
+    name.ends_with(".vy") || name.ends_with(".vyi")
+}
+
+/// The locally installed vyper.
+pub fn vyper() -> Result<Vyper, eyre::Error> {
+    let path = env::var("VYPER").unwrap_or_else(|_| DEFAULT_VYPER.to_string());
+    Vyper::new(&path).map_err(|err| eyre::eyre!("vyper not available at {}: {}", path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_vyper() {
+        assert!(is_vyper("Token.vy"));
+        assert!(is_vyper("interfaces/IToken.vyi"));
+        assert!(!is_vyper("Token.sol"));
+        assert!(!is_vyper("Token.vy.sol"));
+    }
+}
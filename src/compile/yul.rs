@@ -0,0 +1,44 @@
+use alloy_primitives::{hex, Bytes};
+use std::{fs, process::Command};
+use tempfile::TempDir;
+
+use super::{settings::CompileSettings, solc_store::SolcStore};
+
+/// Compiles a Yul object (or a bare Yul block) with `solc --strict-assembly`
+/// and returns its bytecode.
+pub fn compile_yul(source: &str, settings: &CompileSettings) -> Result<Bytes, eyre::Error> {
+    settings.check_yul()?;
+    let temp_dir = TempDir::new()?;
+    let path = temp_dir.path().join("input.yul");
+    fs::write(&path, source)?;
+
+    let solc = SolcStore::from_env().binary(settings.solc_version.as_ref())?;
+    let mut command = Command::new(&solc);
+    command.arg("--strict-assembly").arg("--bin");
+    if settings.optimizer.unwrap_or(false) {
+        command.arg("--optimize");
+        if let Some(runs) = settings.optimizer_runs {
+            command.arg("--optimize-runs").arg(runs.to_string());
+        }
+    }
+    if let Some(evm_version) = settings.evm_version {
+        command.arg("--evm-version").arg(evm_version.as_str());
+    }
+    let output = command
+        .arg(&path)
+        .output()
+        .map_err(|err| eyre::eyre!("Failed to run {}: {}", solc.display(), err))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre::eyre!("Yul compilation failed:\n{}{}", stderr, stdout));
+    }
+
+    let binary = stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Binary representation:"))
+        .nth(1)
+        .ok_or_else(|| eyre::eyre!("No binary in solc output:\n{}", stdout))?;
+    Ok(hex::decode(binary.trim())?.into())
+}
@@ -0,0 +1,143 @@
+use revm::primitives::{SpecId, TxEnv};
+use serde::Serialize;
+
+/// Where a call's gas went. `intrinsic` is `base + calldata + accessList`,
+/// `execution` is everything the EVM spent on top of it (including
+/// `memoryExpansion`), and `gasUsed` is what was charged after the refund.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GasBreakdown {
+    pub intrinsic: u64,
+    pub base: u64,
+    pub calldata: u64,
+    pub calldata_zero_bytes: u64,
+    pub calldata_non_zero_bytes: u64,
+    pub access_list: u64,
+    pub execution: u64,
+    /// Not available for fork execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_expansion: Option<u64>,
+    /// Refund before the EIP-3529 (or pre-London) cap. Not available for fork
+    /// execution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_uncapped: Option<u64>,
+    pub refund: u64,
+    pub gas_used: u64,
+}
+
+impl GasBreakdown {
+    /// Splits off the intrinsic cost of `tx`. `gas_used` and `refund` are as
+    /// revm reports them: net of the refund, and with the cap applied.
+    /// `execution` is the gas spent by the top-level frame; without it, it's
+    /// derived from `gas_used`.
+    pub fn new(
+        tx: &TxEnv,
+        is_create: bool,
+        spec_id: SpecId,
+        execution: Option<u64>,
+        gas_used: u64,
+        refund: u64,
+    ) -> Self {
+        let zero_bytes = tx.data.iter().filter(|byte| **byte == 0).count() as u64;
+        let non_zero_bytes = tx.data.len() as u64 - zero_bytes;
+        let non_zero_cost = if SpecId::enabled(spec_id, SpecId::ISTANBUL) {
+            16
+        } else {
+            68
+        };
+        let calldata = zero_bytes * 4 + non_zero_bytes * non_zero_cost;
+
+        let mut base = 21_000;
+        if is_create {
+            if SpecId::enabled(spec_id, SpecId::HOMESTEAD) {
+                base += 32_000;
+            }
+            // EIP-3860 initcode word cost
+            if SpecId::enabled(spec_id, SpecId::SHANGHAI) {
+                base += 2 * (tx.data.len() as u64).div_ceil(32);
+            }
+        }
+
+        let access_list = if SpecId::enabled(spec_id, SpecId::BERLIN) {
+            tx.access_list
+                .iter()
+                .map(|item| 2_400 + 1_900 * item.storage_keys.len() as u64)
+                .sum()
+        } else {
+            0
+        };
+
+        let intrinsic = base + calldata + access_list;
+        let execution = execution.unwrap_or_else(|| (gas_used + refund).saturating_sub(intrinsic));
+        Self {
+            intrinsic,
+            base,
+            calldata,
+            calldata_zero_bytes: zero_bytes,
+            calldata_non_zero_bytes: non_zero_bytes,
+            access_list,
+            execution,
+            memory_expansion: None,
+            refund_uncapped: None,
+            refund,
+            gas_used,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{execute_calldatas, fixtures, ExecutionEnv, StateOverride, TraceOptions};
+    use alloy_primitives::Bytes;
+
+    #[test]
+    fn test_splits_calldata_and_execution() {
+        let tx = TxEnv {
+            data: Bytes::from(vec![0x60, 0xfe, 0x47, 0xb1, 0x00, 0x00]),
+            ..Default::default()
+        };
+        let breakdown = GasBreakdown::new(&tx, false, SpecId::CANCUN, None, 40_000, 4_800);
+
+        assert_eq!(breakdown.calldata_zero_bytes, 2);
+        assert_eq!(breakdown.calldata_non_zero_bytes, 4);
+        assert_eq!(breakdown.calldata, 2 * 4 + 4 * 16);
+        assert_eq!(breakdown.intrinsic, 21_000 + 72);
+        assert_eq!(breakdown.execution, 44_800 - 21_072);
+    }
+
+    #[test]
+    fn test_breakdown_adds_up_for_real_call() {
+        let (_, results) = execute_calldatas(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7), fixtures::set(0)],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        for result in &results {
+            let breakdown = result.gas_breakdown.as_ref().unwrap();
+            // set(uint256) selector and the value's last byte
+            assert_eq!(
+                breakdown.calldata_non_zero_bytes + breakdown.calldata_zero_bytes,
+                36
+            );
+            assert_eq!(breakdown.intrinsic, 21_000 + breakdown.calldata);
+            assert_eq!(
+                breakdown.intrinsic + breakdown.execution - breakdown.refund,
+                result.gas_used
+            );
+            assert!(breakdown.memory_expansion.unwrap() <= breakdown.execution);
+        }
+
+        // Clearing the slot earns a refund, capped at a fifth of the gas spent
+        let clear = results[1].gas_breakdown.as_ref().unwrap();
+        assert!(clear.refund > 0);
+        assert_eq!(clear.refund, results[1].gas_refunded);
+        assert!(clear.refund <= (clear.intrinsic + clear.execution) / 5);
+        assert!(clear.refund_uncapped.unwrap() >= clear.refund);
+    }
+}
@@ -0,0 +1,179 @@
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Bytes;
+use revm::primitives::Bytecode;
+use serde::Serialize;
+
+use super::{
+    execute_calldatas, Call, Deployment, ExecutionEnv, ExecutionStatus, StateOverride, TraceOptions,
+};
+
+/// One bytecode's deployment, with deltas against the first bytecode.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DeploymentComparison {
+    #[serde(flatten)]
+    pub deployment: Deployment,
+    pub gas_delta: i64,
+    pub code_size_delta: i64,
+}
+
+/// One bytecode's result for a call, with the gas delta against the first
+/// bytecode.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VariantResult {
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+    pub gas_delta: i64,
+    pub output: Bytes,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CallComparison {
+    /// One entry per bytecode, in request order.
+    pub results: Vec<VariantResult>,
+    /// Whether every bytecode ended with the same status and output.
+    pub outputs_match: bool,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    pub deployments: Vec<DeploymentComparison>,
+    pub calls: Vec<CallComparison>,
+}
+
+/// Runs the same calls against each bytecode, each in its own database, and
+/// compares them against the first.
+pub fn compare(
+    bytecodes: Vec<Bytecode>,
+    calls: Vec<Call>,
+    abi: Option<&JsonAbi>,
+    env: ExecutionEnv,
+    state: &StateOverride,
+) -> Result<Comparison, eyre::Error> {
+    if bytecodes.len() < 2 {
+        return Err(eyre::eyre!("Need at least two bytecodes to compare"));
+    }
+
+    let runs = bytecodes
+        .into_iter()
+        .enumerate()
+        .map(|(i, bytecode)| {
+            execute_calldatas(
+                bytecode,
+                calls.clone(),
+                abi,
+                env.clone(),
+                state,
+                &TraceOptions::default(),
+            )
+            .map_err(|err| eyre::eyre!("Bytecode {}: {}", i, err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (baseline, baseline_results) = &runs[0];
+    let deployments = runs
+        .iter()
+        .map(|(deployment, _)| DeploymentComparison {
+            deployment: *deployment,
+            gas_delta: delta(deployment.gas_used, baseline.gas_used),
+            code_size_delta: deployment.code_size as i64 - baseline.code_size as i64,
+        })
+        .collect();
+
+    let calls = baseline_results
+        .iter()
+        .enumerate()
+        .map(|(i, expected)| {
+            let results: Vec<_> = runs
+                .iter()
+                .map(|(_, results)| VariantResult {
+                    status: results[i].status,
+                    gas_used: results[i].gas_used,
+                    gas_delta: delta(results[i].gas_used, expected.gas_used),
+                    output: results[i].output.clone(),
+                })
+                .collect();
+            let outputs_match = results
+                .iter()
+                .all(|result| result.status == expected.status && result.output == expected.output);
+            CallComparison {
+                results,
+                outputs_match,
+            }
+        })
+        .collect();
+
+    Ok(Comparison { deployments, calls })
+}
+
+fn delta(gas: u64, baseline: u64) -> i64 {
+    gas as i64 - baseline as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::fixtures;
+
+    // PUSH1 1 PUSH0 MSTORE PUSH1 32 PUSH0 RETURN
+    const RETURN_ONE: [u8; 8] = [0x60, 0x01, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xf3];
+    // Same output, with a wasted DUP1 POP before the MSTORE
+    const RETURN_ONE_SLOWER: [u8; 10] =
+        [0x60, 0x01, 0x80, 0x50, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xf3];
+    // PUSH1 2 PUSH0 MSTORE PUSH1 32 PUSH0 RETURN
+    const RETURN_TWO: [u8; 8] = [0x60, 0x02, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xf3];
+
+    fn run(runtimes: &[&[u8]]) -> Comparison {
+        compare(
+            runtimes
+                .iter()
+                .map(|runtime| fixtures::initcode(runtime))
+                .collect(),
+            vec![Call::default()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_compare_matching_outputs() {
+        let comparison = run(&[&RETURN_ONE, &RETURN_ONE_SLOWER]);
+
+        let call = &comparison.calls[0];
+        assert!(call.outputs_match);
+        assert_eq!(call.results[0].gas_delta, 0);
+        // DUP1 and POP cost 3 and 2
+        assert_eq!(call.results[1].gas_delta, 5);
+
+        let deployments = &comparison.deployments;
+        assert_eq!(deployments[1].code_size_delta, 2);
+        assert!(deployments[1].gas_delta > 0);
+    }
+
+    #[test]
+    fn test_compare_different_outputs() {
+        let comparison = run(&[&RETURN_ONE, &RETURN_ONE_SLOWER, &RETURN_TWO]);
+        let call = &comparison.calls[0];
+        assert!(!call.outputs_match);
+        assert_eq!(call.results.len(), 3);
+        assert_eq!(call.results[2].output[31], 2);
+    }
+
+    #[test]
+    fn test_compare_needs_two_bytecodes() {
+        let err = compare(
+            vec![fixtures::initcode(&RETURN_ONE)],
+            vec![],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("at least two"));
+    }
+}
@@ -2,25 +2,69 @@ use alloy_primitives::{Address, Bytes};
 use revm::{
     db::CacheDB,
     primitives::{ExecutionResult, TransactTo},
-    Evm, InMemoryDB,
+    DatabaseRef, Evm, InMemoryDB,
 };
+use serde::Serialize;
 
-pub fn deploy(bytecode: Bytes, db: &mut CacheDB<InMemoryDB>) -> Result<Address, eyre::Error> {
+use super::ExecutionEnv;
+
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+    pub address: Address,
+    pub gas_used: u64,
+    /// Size of the runtime code left at `address`.
+    pub code_size: usize,
+}
+
+pub fn deploy(
+    bytecode: Bytes,
+    env: &ExecutionEnv,
+    db: &mut CacheDB<InMemoryDB>,
+) -> Result<Deployment, eyre::Error> {
     let mut evm = Evm::builder()
-        .with_db(db)
+        .with_db(&mut *db)
+        .with_spec_id(env.spec_id)
+        .with_block_env(env.block.clone())
+        .modify_cfg_env(|cfg| *cfg = env.cfg.clone())
         .modify_tx_env(|tx| {
             tx.transact_to = TransactTo::Create;
             tx.data = bytecode;
+            tx.gas_limit = env.block.gas_limit.saturating_to();
         })
         .build();
     let result = evm.transact_commit()?;
+    drop(evm);
 
-    if let ExecutionResult::Success { output, .. } = result {
-        let address = output
+    if let ExecutionResult::Success {
+        output, gas_used, ..
+    } = result
+    {
+        let address = *output
             .address()
             .ok_or(eyre::eyre!("No address in execution result output"))?;
-        return Ok(*address);
+        let code_size = db
+            .basic_ref(address)?
+            .and_then(|info| info.code)
+            .map_or(0, |code| code.original_bytes().len());
+        Ok(Deployment {
+            address,
+            gas_used,
+            code_size,
+        })
     } else {
         Err(eyre::eyre!("Execution failed {:?}", result))
     }
 }
+
+/// Runs `initcode` in a scratch database and returns the runtime code it
+/// deploys.
+pub fn deployed_code(initcode: Bytes, env: &ExecutionEnv) -> Result<Bytes, eyre::Error> {
+    let mut db = CacheDB::new(InMemoryDB::default());
+    let deployment = deploy(initcode, env, &mut db)?;
+    Ok(db
+        .basic_ref(deployment.address)?
+        .and_then(|info| info.code)
+        .map(|code| code.original_bytes())
+        .unwrap_or_default())
+}
@@ -0,0 +1,149 @@
+use alloy_primitives::{Address, B256, U256};
+use revm::primitives::{BlockEnv, CfgEnv, SpecId};
+use serde::Deserialize;
+
+use super::Hardfork;
+
+/// Block and chain values a request can set for local execution. Anything left
+/// out keeps revm's default.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct BlockConfig {
+    pub number: Option<U256>,
+    pub timestamp: Option<U256>,
+    pub basefee: Option<U256>,
+    pub chain_id: Option<u64>,
+    pub coinbase: Option<Address>,
+    pub gas_limit: Option<U256>,
+    pub prevrandao: Option<B256>,
+    pub difficulty: Option<U256>,
+}
+
+/// Environment every deploy and call of a local run executes in.
+#[derive(Clone, Debug)]
+pub struct ExecutionEnv {
+    pub block: BlockEnv,
+    pub cfg: CfgEnv,
+    pub spec_id: SpecId,
+}
+
+impl Default for ExecutionEnv {
+    fn default() -> Self {
+        let mut cfg = CfgEnv::default();
+        // Calls are sent with a zero gas price, so a configured basefee should
+        // only be visible to BASEFEE rather than rejecting every transaction.
+        cfg.disable_base_fee = true;
+        Self {
+            block: BlockEnv::default(),
+            cfg,
+            spec_id: SpecId::LATEST,
+        }
+    }
+}
+
+impl ExecutionEnv {
+    pub fn new(config: &BlockConfig, hardfork: Option<Hardfork>) -> Self {
+        let mut env = Self::default();
+        if let Some(hardfork) = hardfork {
+            env.spec_id = hardfork.into();
+        }
+        let block = &mut env.block;
+        if let Some(number) = config.number {
+            block.number = number;
+        }
+        if let Some(timestamp) = config.timestamp {
+            block.timestamp = timestamp;
+        }
+        if let Some(basefee) = config.basefee {
+            block.basefee = basefee;
+        }
+        if let Some(coinbase) = config.coinbase {
+            block.coinbase = coinbase;
+        }
+        if let Some(gas_limit) = config.gas_limit {
+            block.gas_limit = gas_limit;
+        }
+        if let Some(prevrandao) = config.prevrandao {
+            block.prevrandao = Some(prevrandao);
+        }
+        if let Some(difficulty) = config.difficulty {
+            block.difficulty = difficulty;
+        }
+        if let Some(chain_id) = config.chain_id {
+            env.cfg.chain_id = chain_id;
+        }
+        env
+    }
+
+    /// Moves the block forward like `vm.warp`/`vm.roll`, for this and all later calls.
+    pub fn warp_and_roll(&mut self, timestamp: Option<U256>, number: Option<U256>) {
+        if let Some(timestamp) = timestamp {
+            self.block.timestamp = timestamp;
+        }
+        if let Some(number) = number {
+            self.block.number = number;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{execute_calldatas, fixtures, Call, StateOverride, TraceOptions};
+
+    fn block_of(output: &[u8]) -> (U256, U256) {
+        (
+            U256::from_be_slice(&output[..32]),
+            U256::from_be_slice(&output[32..64]),
+        )
+    }
+
+    #[test]
+    fn test_warp_and_roll_persist_between_calls() {
+        // Returns (block.timestamp, block.number)
+        let bytecode = fixtures::initcode(&[
+            0x42, 0x5f, 0x52, 0x43, 0x60, 0x20, 0x52, 0x60, 0x40, 0x5f, 0xf3,
+        ]);
+        let config = BlockConfig {
+            number: Some(U256::from(1)),
+            timestamp: Some(U256::from(5)),
+            ..Default::default()
+        };
+        let calls = vec![
+            Call::default(),
+            Call {
+                warp: Some(U256::from(1000)),
+                roll: Some(U256::from(50)),
+                ..Default::default()
+            },
+            Call::default(),
+            Call {
+                roll: Some(U256::from(60)),
+                ..Default::default()
+            },
+        ];
+
+        let (_, results) = execute_calldatas(
+            bytecode,
+            calls,
+            None,
+            ExecutionEnv::new(&config, None),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        let blocks: Vec<_> = results
+            .iter()
+            .map(|result| block_of(&result.output))
+            .collect();
+        assert_eq!(
+            blocks,
+            [
+                (U256::from(5), U256::from(1)),
+                (U256::from(1000), U256::from(50)),
+                (U256::from(1000), U256::from(50)),
+                (U256::from(1000), U256::from(60)),
+            ]
+        );
+    }
+}
@@ -1,30 +1,132 @@
+use alloy_json_abi::{Function, JsonAbi};
 use alloy_primitives::{Address, Bytes, U256};
-use revm::{
-    db::CacheDB,
-    primitives::{Bytecode, ExecutionResult},
-    InMemoryDB,
-};
+use revm::{db::CacheDB, primitives::Bytecode, InMemoryDB};
 use serde::Deserialize;
+use serde_json::Value;
 
-use super::{deploy, transact};
+use super::{
+    apply_state_override, deploy, transact, Deployment, ExecutionEnv, ExecutionResult,
+    StateOverride, TraceOptions, TransactResult,
+};
+use crate::abi::{decode_result, encode_function_call, find_function};
 
-#[derive(Deserialize, Clone)]
+#[derive(Deserialize, Clone, Default)]
 pub struct Call {
     pub calldata: Option<Bytes>,
     pub value: Option<U256>,
     pub caller: Option<Address>,
+    /// Function signature (`set(uint256)`) or, with an ABI, a function name.
+    /// Used with `args` instead of raw `calldata`.
+    pub function: Option<String>,
+    pub args: Option<Vec<Value>>,
+    /// Sets `block.timestamp` before this call, and keeps it for later calls.
+    pub warp: Option<U256>,
+    /// Sets `block.number` before this call, and keeps it for later calls.
+    pub roll: Option<U256>,
 }
 
+/// Deploys `bytecode` into a fresh database and runs `calls` against it in order.
 pub fn execute_calldatas(
     bytecode: Bytecode,
     calls: Vec<Call>,
-) -> Result<Vec<ExecutionResult>, eyre::Error> {
+    abi: Option<&JsonAbi>,
+    mut env: ExecutionEnv,
+    state: &StateOverride,
+    trace: &TraceOptions,
+) -> Result<(Deployment, Vec<ExecutionResult>), eyre::Error> {
     let mut db = CacheDB::new(InMemoryDB::default());
+    apply_state_override(state, &mut db)?;
 
-    let address = deploy(bytecode.bytes(), &mut db)?;
+    let calls = calls
+        .into_iter()
+        .map(|call| encode_call(call, abi))
+        .collect::<Result<Vec<_>, _>>()?;
+    let deployment = deploy(bytecode.bytes(), &env, &mut db)?;
 
-    calls
+    let results = calls
         .into_iter()
-        .map(|call| transact(address, call.calldata, call.value, call.caller, &mut db))
-        .collect()
+        .map(|call| execute_call(deployment.address, call, abi, &mut env, trace, &mut db))
+        .collect::<Result<_, _>>()?;
+    Ok((deployment, results))
+}
+
+/// A call with its calldata encoded and its function looked up, so a batch
+/// can be checked before any of it runs.
+#[derive(Clone)]
+pub struct EncodedCall {
+    call: Call,
+    function: Option<Function>,
+    calldata: Option<Bytes>,
+}
+
+/// Encodes a call's arguments when a function signature or ABI is available.
+pub fn encode_call(call: Call, abi: Option<&JsonAbi>) -> Result<EncodedCall, eyre::Error> {
+    let (function, calldata) = match &call.function {
+        Some(_) if call.calldata.is_some() => {
+            return Err(eyre::eyre!("Pass either calldata or function, not both"))
+        }
+        None if call.args.is_some() => {
+            return Err(eyre::eyre!("args need a function to encode them for"))
+        }
+        Some(function) => {
+            let (function, calldata) =
+                encode_function_call(abi, function, call.args.as_deref().unwrap_or_default())?;
+            (Some(function), Some(calldata))
+        }
+        None => {
+            let function = abi
+                .zip(call.calldata.as_ref())
+                .and_then(|(abi, calldata)| find_function(abi, calldata))
+                .cloned();
+            (function, call.calldata.clone())
+        }
+    };
+    Ok(EncodedCall {
+        call,
+        function,
+        calldata,
+    })
+}
+
+/// Executes a single call, decoding its result when a function signature or
+/// ABI is available.
+pub fn execute_call(
+    address: Address,
+    call: EncodedCall,
+    abi: Option<&JsonAbi>,
+    env: &mut ExecutionEnv,
+    trace: &TraceOptions,
+    db: &mut CacheDB<InMemoryDB>,
+) -> Result<ExecutionResult, eyre::Error> {
+    let EncodedCall {
+        call,
+        function,
+        calldata,
+    } = call;
+    env.warp_and_roll(call.warp, call.roll);
+    let TransactResult {
+        result,
+        traces,
+        state_diff,
+        struct_logs,
+        geth_trace,
+        gas_breakdown,
+    } = transact(address, calldata, call.value, call.caller, env, trace, db)?;
+    let mut result = ExecutionResult::from_revm(result, traces);
+    result.state_diff = Some(state_diff);
+    result.struct_logs = struct_logs;
+    result.geth_trace = geth_trace;
+    result.gas_breakdown = Some(gas_breakdown);
+
+    if function.is_some() || abi.is_some() {
+        result.decoded = Some(decode_result(
+            function.as_ref(),
+            abi,
+            &result.output,
+            !result.is_success(),
+            &result.logs,
+        ));
+    }
+
+    Ok(result)
 }
@@ -3,18 +3,28 @@ use std::env;
 
 use alloy::providers::{Provider, ProviderBuilder};
 use alloy_eips::BlockId;
-use alloy_primitives::{Address, Bytes, Log, U256};
+use alloy_primitives::{Address, Bytes, U256};
 use alloy_rpc_types_eth::BlockTransactionsKind;
+use forge::executors::RawCallResult;
 use forge::{
     backend::{self},
     executors::ExecutorBuilder,
     opts::EvmOpts,
-    traces::CallTraceArena,
+    traces::{CallTraceArena, TraceMode},
 };
 use foundry_config::Config;
-use revm::{interpreter::InstructionResult, primitives::TxEnv};
+use revm::{
+    interpreter::{InstructionResult, SuccessOrHalt},
+    primitives::{ExecutionResult as RevmExecutionResult, Output, ResultAndState, TxEnv},
+};
+use revm_inspectors::tracing::{GethTraceBuilder, TracingInspectorConfig};
 use revm_primitives::{AccountInfo, BlockEnv, Bytecode, CfgEnv, Env};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+
+use super::{
+    clear_steps, geth_trace, halt_reason, struct_logs, ExecutionResult, ExecutionStatus,
+    GasBreakdown, GethTracer, Hardfork, TraceOptions, EXECUTION_RESULT_VERSION,
+};
 
 #[derive(Deserialize, Clone)]
 pub struct Call {
@@ -23,22 +33,18 @@ pub struct Call {
     pub caller: Address,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-#[serde(rename_all = "camelCase")]
-pub struct ExecutionResult {
-    pub exit_reason: InstructionResult,
-    pub reverted: bool,
-    pub result: Bytes,
-    pub gas_used: u64,
-    pub logs: Vec<Log>,
-    pub traces: CallTraceArena,
-}
-
 pub async fn execute_calldatas_fork(
     deployed_bytes: Bytes,
     address: Address,
     calls: Vec<Call>,
+    hardfork: Option<Hardfork>,
+    trace: &TraceOptions,
 ) -> Result<Vec<ExecutionResult>, eyre::Error> {
+    if trace.tracer == Some(GethTracer::FourByteTracer) {
+        return Err(eyre::eyre!(
+            "4byteTracer is only supported for local execution"
+        ));
+    }
     dotenv().ok();
     let rpc =
         env::var("BASE_RPC").map_err(|_| eyre::eyre!("BASE_RPC environment variable not set"))?;
@@ -66,12 +72,13 @@ pub async fn execute_calldatas_fork(
         gas_limit: U256::from(block.header.gas_limit),
         ..Default::default()
     };
+    let gas_limit = block.header.gas_limit as u64;
     let env = Env {
         cfg,
         block: block_env,
         tx: TxEnv {
             chain_id: Some(rpc_chain_id),
-            gas_limit: block.header.gas_limit as u64,
+            gas_limit,
             ..Default::default()
         },
         ..Default::default()
@@ -81,9 +88,19 @@ pub async fn execute_calldatas_fork(
         ..Default::default()
     };
     let backend = backend::Backend::spawn(opts.get_fork(&Config::default(), opts.evm_env().await?));
-    let mut executor = ExecutorBuilder::new()
-        .inspectors(|stack| stack.trace_mode(forge::traces::TraceMode::Call).logs(true))
-        .build(env, backend);
+    // Debug mode is the forge trace mode that records every step with its
+    // stack and memory.
+    let trace_mode = if trace.step_trace.is_some() {
+        TraceMode::Debug
+    } else {
+        TraceMode::Call
+    };
+    let mut builder =
+        ExecutorBuilder::new().inspectors(|stack| stack.trace_mode(trace_mode).logs(true));
+    if let Some(hardfork) = hardfork {
+        builder = builder.spec(hardfork.into());
+    }
+    let mut executor = builder.build(env, backend);
 
     let deployed_bytecode = Bytecode::new_raw(deployed_bytes);
     executor.backend_mut().insert_account_info(
@@ -98,19 +115,108 @@ pub async fn execute_calldatas_fork(
     calls
         .into_iter()
         .map(|call| {
-            let r = executor.transact_raw(call.caller, address, call.calldata, call.value)?;
+            // `transact_raw` commits, so keep the pre-transaction backend to
+            // read original values from. Forked state lives in a cache shared
+            // between clones, so this doesn't fetch anything twice.
+            let backend_before = trace.wants_prestate().then(|| executor.backend().clone());
+            let tx = TxEnv {
+                data: call.calldata.clone(),
+                ..Default::default()
+            };
+            let mut r = executor.transact_raw(call.caller, address, call.calldata, call.value)?;
+            let mut traces = r.traces.take().unwrap_or(CallTraceArena::default());
+            // The root trace's gas is net of its refund, so add that back
+            let execution = traces
+                .nodes()
+                .first()
+                .map(|node| node.trace.gas_used + r.gas_refunded);
+            let gas_breakdown = GasBreakdown::new(
+                &tx,
+                false,
+                executor.spec_id(),
+                execution,
+                r.gas_used,
+                r.gas_refunded,
+            );
+            let geth_trace = trace
+                .tracer
+                .map(|tracer| {
+                    let result = ResultAndState {
+                        result: revm_result(&r)?,
+                        state: r.state_changeset.clone(),
+                    };
+                    let builder = GethTraceBuilder::new(
+                        traces.nodes().to_vec(),
+                        TracingInspectorConfig::default_geth(),
+                    );
+                    geth_trace(
+                        tracer,
+                        &trace.tracer_config,
+                        &builder,
+                        None,
+                        gas_limit,
+                        &result,
+                        backend_before.as_ref().unwrap_or(executor.backend()),
+                    )
+                })
+                .transpose()?;
+            let struct_logs = trace.step_trace.as_ref().map(|options| {
+                let logs = struct_logs(&traces, options);
+                clear_steps(&mut traces);
+                logs
+            });
+            let status = if !r.reverted {
+                ExecutionStatus::Success
+            } else if r.exit_reason == InstructionResult::Revert {
+                ExecutionStatus::Revert
+            } else {
+                ExecutionStatus::Halt
+            };
             Ok(ExecutionResult {
-                exit_reason: r.exit_reason,
-                reverted: r.reverted,
-                result: r.result,
+                version: EXECUTION_RESULT_VERSION,
+                status,
                 gas_used: r.gas_used,
+                gas_refunded: r.gas_refunded,
+                gas_breakdown: Some(gas_breakdown),
+                output: r.result,
                 logs: r.logs,
-                traces: r.traces.unwrap_or(CallTraceArena::default()),
+                traces,
+                created_address: None,
+                halt_reason: SuccessOrHalt::from(r.exit_reason)
+                    .to_halt()
+                    .map(halt_reason),
+                decoded: None,
+                state_diff: None,
+                struct_logs,
+                geth_trace,
             })
         })
         .collect()
 }
 
+/// Rebuilds the result revm would have returned from forge's, for the geth
+/// trace builder.
+fn revm_result(r: &RawCallResult) -> Result<RevmExecutionResult, eyre::Error> {
+    Ok(match SuccessOrHalt::from(r.exit_reason) {
+        SuccessOrHalt::Success(reason) => RevmExecutionResult::Success {
+            reason,
+            gas_used: r.gas_used,
+            gas_refunded: r.gas_refunded,
+            logs: r.logs.clone(),
+            output: Output::Call(r.result.clone()),
+        },
+        SuccessOrHalt::Revert => RevmExecutionResult::Revert {
+            gas_used: r.gas_used,
+            output: r.result.clone(),
+        },
+        SuccessOrHalt::Halt(reason) => RevmExecutionResult::Halt {
+            reason,
+            gas_used: r.gas_used,
+        },
+        _ => return Err(eyre::eyre!("Unexpected exit reason {:?}", r.exit_reason)),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,22 +249,28 @@ mod tests {
         };
 
         // Execute the calls
-        let results = execute_calldatas_fork(bytecode, address, vec![store_call, retrieve_call])
-            .await
-            .unwrap();
+        let results = execute_calldatas_fork(
+            bytecode,
+            address,
+            vec![store_call, retrieve_call],
+            None,
+            &TraceOptions::default(),
+        )
+        .await
+        .unwrap();
 
         for (i, result) in results.iter().enumerate() {
             println!("Call {}", i);
-            println!("Result data: 0x{}", hex::encode(&result.result));
+            println!("Result data: 0x{}", hex::encode(&result.output));
             println!("Gas used: {}", result.gas_used);
-            println!("Exit reason: {:?}", result.exit_reason);
-            println!("Reverted: {}", result.reverted);
+            println!("Status: {:?}", result.status);
+            println!("Halt reason: {:?}", result.halt_reason);
             println!("---");
         }
 
         // Check the retrieve call result
         assert_eq!(
-            hex::encode(&results[1].result),
+            hex::encode(&results[1].output),
             "0000000000000000000000000000000000000000000000000000000000000001"
         );
     }
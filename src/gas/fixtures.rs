@@ -0,0 +1,42 @@
+//! Contracts shared by the in-process execution tests.
+
+use alloy_primitives::hex;
+use revm::primitives::Bytecode;
+
+use super::Call;
+
+/// Runtime code of a storage contract with `set(uint256)`, which stores the
+/// value in slot 0 and emits it, `get()`, `storedData()` and `getBlockNumber()`.
+pub const SIMPLE_STORAGE: &str = "608060405234801561000f575f80fd5b506004361061004a575f3560e01c80632a1afcd91461004e57806342cbb15c1461006c57806360fe47b11461008a5780636d4ce63c146100a6575b5f80fd5b6100566100c4565b6040516100639190610130565b60405180910390f35b6100746100c9565b6040516100819190610130565b60405180910390f35b6100a4600480360381019061009f9190610177565b6100d0565b005b6100ae610110565b6040516100bb9190610130565b60405180910390f35b5f5481565b5f43905090565b805f819055507fe0dca1a932506e28dc1cd7f50b0604489287b36ba09c37f13b25ee518d813528816040516101059190610130565b60405180910390a150565b5f8054905090565b5f819050919050565b61012a81610118565b82525050565b5f6020820190506101435f830184610121565b92915050565b5f80fd5b61015681610118565b8114610160575f80fd5b50565b5f813590506101718161014d565b92915050565b5f6020828403121561018c5761018b610149565b5b5f61019984828501610163565b9150509291505056fea2646970667358221220f7399e877793618afbf93c1ab591511f69fa1330a3fd5526ff45418127a04af964736f6c634300081a0033";
+
+/// Wraps `runtime` in initcode that deploys it unchanged.
+pub fn initcode(runtime: &[u8]) -> Bytecode {
+    let len = u16::try_from(runtime.len())
+        .expect("runtime code too large")
+        .to_be_bytes();
+    // PUSH2 len DUP1 PUSH1 12 PUSH1 0 CODECOPY PUSH1 0 RETURN
+    let mut code = vec![
+        0x61, len[0], len[1], 0x80, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, 0x00, 0xf3,
+    ];
+    code.extend_from_slice(runtime);
+    Bytecode::new_raw(code.into())
+}
+
+pub fn simple_storage() -> Bytecode {
+    initcode(&hex::decode(SIMPLE_STORAGE).unwrap())
+}
+
+pub fn call(calldata: &str) -> Call {
+    Call {
+        calldata: Some(hex::decode(calldata).unwrap().into()),
+        ..Default::default()
+    }
+}
+
+pub fn set(value: u64) -> Call {
+    call(&format!("60fe47b1{:064x}", value))
+}
+
+pub fn get() -> Call {
+    call("6d4ce63c")
+}
@@ -0,0 +1,171 @@
+use alloy_primitives::U256;
+use alloy_rpc_types_trace::geth::{CallConfig, FourByteFrame, GethTrace, PreStateConfig};
+use revm::{primitives::ResultAndState, DatabaseRef};
+use revm_inspectors::tracing::{FourByteInspector, GethTraceBuilder};
+use serde::Deserialize;
+
+/// Geth's built-in tracers that can be rebuilt from what we record.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GethTracer {
+    #[serde(rename = "callTracer")]
+    CallTracer,
+    #[serde(rename = "prestateTracer")]
+    PrestateTracer,
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GethTracerConfig {
+    /// callTracer: only return the top-level call.
+    #[serde(default)]
+    pub only_top_call: bool,
+    /// prestateTracer: return `pre` and `post` for changed accounts only.
+    #[serde(default)]
+    pub diff_mode: bool,
+}
+
+/// Builds the output of `tracer` from a transaction's uncommitted result and
+/// the database as it was before the transaction. `gas_limit` is the
+/// transaction's, which geth reports on the root call frame.
+pub fn geth_trace<DB: DatabaseRef>(
+    tracer: GethTracer,
+    config: &GethTracerConfig,
+    builder: &GethTraceBuilder,
+    four_byte: Option<FourByteInspector>,
+    gas_limit: u64,
+    result: &ResultAndState,
+    db: DB,
+) -> Result<GethTrace, eyre::Error>
+where
+    DB::Error: std::fmt::Debug,
+{
+    let trace = match tracer {
+        GethTracer::CallTracer => {
+            let call_config = CallConfig {
+                only_top_call: Some(config.only_top_call),
+                ..Default::default()
+            };
+            let mut frame = builder.geth_call_traces(call_config, result.result.gas_used());
+            // The recorded root frame only got what was left after intrinsic gas
+            frame.gas = U256::from(gas_limit);
+            GethTrace::CallTracer(frame)
+        }
+        GethTracer::PrestateTracer => {
+            let prestate_config = PreStateConfig {
+                diff_mode: Some(config.diff_mode),
+                ..Default::default()
+            };
+            let frame = builder
+                .geth_prestate_traces(result, &prestate_config, db)
+                .map_err(|err| eyre::eyre!("Failed to build prestate trace: {:?}", err))?;
+            GethTrace::PreStateTracer(frame)
+        }
+        GethTracer::FourByteTracer => {
+            let four_byte = four_byte.ok_or(eyre::eyre!("4byteTracer wasn't recorded"))?;
+            GethTrace::FourByteTracer(FourByteFrame::from(four_byte))
+        }
+    };
+    Ok(trace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        execute_calldatas, fixtures, Call, ExecutionEnv, ExecutionResult, StateOverride,
+        TraceOptions,
+    };
+    use alloy_primitives::{Address, B256};
+    use alloy_rpc_types_trace::geth::PreStateFrame;
+    use revm::primitives::Bytecode;
+    use serde_json::json;
+
+    fn run(
+        bytecode: Bytecode,
+        calls: Vec<Call>,
+        tracer: GethTracer,
+        tracer_config: GethTracerConfig,
+    ) -> (Address, Vec<ExecutionResult>) {
+        let trace = TraceOptions {
+            tracer: Some(tracer),
+            tracer_config,
+            ..Default::default()
+        };
+        let (deployment, results) = execute_calldatas(
+            bytecode,
+            calls,
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &trace,
+        )
+        .unwrap();
+        (deployment.address, results)
+    }
+
+    #[test]
+    fn test_tracer_config_uses_geth_names() {
+        let config: GethTracerConfig =
+            serde_json::from_str(r#"{"onlyTopCall": true, "diffMode": true}"#).unwrap();
+        assert!(config.only_top_call);
+        assert!(config.diff_mode);
+    }
+
+    #[test]
+    fn test_call_tracer_root_includes_intrinsic_gas() {
+        let (address, results) = run(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7)],
+            GethTracer::CallTracer,
+            GethTracerConfig::default(),
+        );
+        let Some(GethTrace::CallTracer(frame)) = &results[0].geth_trace else {
+            panic!("expected a callTracer frame");
+        };
+        assert_eq!(frame.to, Some(address));
+        assert_eq!(frame.gas, U256::from(u64::MAX));
+        assert_eq!(frame.gas_used, U256::from(results[0].gas_used));
+    }
+
+    #[test]
+    fn test_prestate_diff() {
+        let (address, results) = run(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7)],
+            GethTracer::PrestateTracer,
+            GethTracerConfig {
+                diff_mode: true,
+                ..Default::default()
+            },
+        );
+        let Some(GethTrace::PreStateTracer(PreStateFrame::Diff(diff))) = &results[0].geth_trace
+        else {
+            panic!("expected a prestateTracer diff");
+        };
+        assert_eq!(
+            diff.post[&address].storage[&B256::ZERO],
+            B256::from(U256::from(7))
+        );
+    }
+
+    #[test]
+    fn test_four_byte_skips_precompiles() {
+        // CALL(gas, 0x04, 0, 0, 36, 0, 0) to the identity precompile, with
+        // 36 bytes of calldata so it would otherwise be counted
+        let bytecode = fixtures::initcode(&[
+            0x5f, 0x5f, 0x60, 0x24, 0x5f, 0x5f, 0x60, 0x04, 0x5a, 0xf1, 0x00,
+        ]);
+        let (_, results) = run(
+            bytecode,
+            vec![fixtures::get()],
+            GethTracer::FourByteTracer,
+            GethTracerConfig::default(),
+        );
+        assert_eq!(
+            serde_json::to_value(results[0].geth_trace.as_ref().unwrap()).unwrap(),
+            json!({ "0x6d4ce63c-0": 1 })
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use revm::primitives::SpecId;
+use serde::Deserialize;
+
+/// EVM spec a request executes under, named the way users write them.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Hardfork {
+    Frontier,
+    Homestead,
+    Tangerine,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    #[serde(alias = "merge")]
+    Paris,
+    Shanghai,
+    Cancun,
+    Prague,
+}
+
+impl From<Hardfork> for SpecId {
+    fn from(hardfork: Hardfork) -> Self {
+        match hardfork {
+            Hardfork::Frontier => SpecId::FRONTIER,
+            Hardfork::Homestead => SpecId::HOMESTEAD,
+            Hardfork::Tangerine => SpecId::TANGERINE,
+            Hardfork::SpuriousDragon => SpecId::SPURIOUS_DRAGON,
+            Hardfork::Byzantium => SpecId::BYZANTIUM,
+            Hardfork::Constantinople => SpecId::CONSTANTINOPLE,
+            Hardfork::Petersburg => SpecId::PETERSBURG,
+            Hardfork::Istanbul => SpecId::ISTANBUL,
+            Hardfork::Berlin => SpecId::BERLIN,
+            Hardfork::London => SpecId::LONDON,
+            Hardfork::Paris => SpecId::MERGE,
+            Hardfork::Shanghai => SpecId::SHANGHAI,
+            Hardfork::Cancun => SpecId::CANCUN,
+            Hardfork::Prague => SpecId::PRAGUE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        execute_calldatas, fixtures, BlockConfig, Call, ExecutionEnv, ExecutionStatus,
+        StateOverride, TraceOptions,
+    };
+
+    fn status_under(hardfork: Hardfork, runtime: &[u8]) -> ExecutionStatus {
+        let (_, results) = execute_calldatas(
+            fixtures::initcode(runtime),
+            vec![Call::default()],
+            None,
+            ExecutionEnv::new(&BlockConfig::default(), Some(hardfork)),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+        results[0].status
+    }
+
+    #[test]
+    fn test_hardfork_names() {
+        let parse = |name: &str| serde_json::from_value::<Hardfork>(name.into()).unwrap();
+        assert_eq!(SpecId::from(parse("merge")), SpecId::MERGE);
+        assert_eq!(SpecId::from(parse("paris")), SpecId::MERGE);
+        assert_eq!(
+            SpecId::from(parse("spurious_dragon")),
+            SpecId::SPURIOUS_DRAGON
+        );
+        assert_eq!(SpecId::from(parse("cancun")), SpecId::CANCUN);
+        assert!(serde_json::from_value::<Hardfork>("osaka".into()).is_err());
+    }
+
+    #[test]
+    fn test_hardfork_selects_opcodes() {
+        // PUSH0 PUSH0 RETURN
+        let push0 = [0x5f, 0x5f, 0xf3];
+        assert_eq!(status_under(Hardfork::Paris, &push0), ExecutionStatus::Halt);
+        assert_eq!(
+            status_under(Hardfork::Shanghai, &push0),
+            ExecutionStatus::Success
+        );
+
+        // PUSH1 0 TLOAD STOP
+        let tload = [0x60, 0x00, 0x5c, 0x00];
+        assert_eq!(
+            status_under(Hardfork::Shanghai, &tload),
+            ExecutionStatus::Halt
+        );
+        assert_eq!(
+            status_under(Hardfork::Cancun, &tload),
+            ExecutionStatus::Success
+        );
+    }
+}
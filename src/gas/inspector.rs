@@ -0,0 +1,155 @@
+use alloy_primitives::{Address, Log, U256};
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use revm_inspectors::tracing::{FourByteInspector, TracingInspector, TracingInspectorConfig};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TransientWrite {
+    pub address: Address,
+    pub slot: U256,
+    pub value: U256,
+}
+
+/// Inspector for local execution. Wraps the call tracer and additionally
+/// records TSTOREs, since transient storage is gone by the time revm hands back
+/// the transaction's state, and the gas accounting revm doesn't report.
+#[derive(Debug)]
+pub struct ExecutionInspector {
+    pub tracer: TracingInspector,
+    /// Only set when the 4byteTracer was requested.
+    pub four_byte: Option<FourByteInspector>,
+    pub transient_writes: Vec<TransientWrite>,
+    /// Gas charged for memory expansion, across all frames.
+    pub memory_expansion_gas: u64,
+    /// Refund accumulated by the top-level frame, before the refund cap.
+    pub uncapped_refund: u64,
+    /// Gas spent by the top-level frame, before refunds.
+    pub execution_gas: u64,
+    /// Memory size of the current frame when the current opcode started.
+    memory_len: usize,
+    /// `transient_writes.len()` at the start of each open call frame, so writes
+    /// made by frames that revert can be dropped again.
+    checkpoints: Vec<usize>,
+}
+
+impl ExecutionInspector {
+    pub fn new(config: TracingInspectorConfig) -> Self {
+        Self {
+            tracer: TracingInspector::new(config),
+            four_byte: None,
+            transient_writes: vec![],
+            memory_expansion_gas: 0,
+            uncapped_refund: 0,
+            execution_gas: 0,
+            memory_len: 0,
+            checkpoints: vec![],
+        }
+    }
+
+    fn enter_frame(&mut self) {
+        self.checkpoints.push(self.transient_writes.len());
+    }
+
+    fn exit_frame(&mut self, success: bool, gas: &Gas) {
+        if let Some(checkpoint) = self.checkpoints.pop() {
+            if !success {
+                self.transient_writes.truncate(checkpoint);
+            }
+        }
+        if self.checkpoints.is_empty() {
+            self.execution_gas = gas.spent();
+            if success {
+                self.uncapped_refund = gas.refunded().max(0) as u64;
+            }
+        }
+    }
+}
+
+/// Total cost of a memory of `len` bytes: 3 gas per word plus words² / 512.
+fn memory_gas(len: usize) -> u64 {
+    let words = len.div_ceil(32) as u64;
+    3 * words + words * words / 512
+}
+
+impl<DB: Database> Inspector<DB> for ExecutionInspector {
+    fn initialize_interp(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        self.tracer.initialize_interp(interp, context);
+    }
+
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        if interp.current_opcode() == opcode::TSTORE {
+            if let (Ok(slot), Ok(value)) = (interp.stack().peek(0), interp.stack().peek(1)) {
+                self.transient_writes.push(TransientWrite {
+                    address: interp.contract.target_address,
+                    slot,
+                    value,
+                });
+            }
+        }
+        self.memory_len = interp.shared_memory.len();
+        self.tracer.step(interp, context);
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<DB>) {
+        let memory_len = interp.shared_memory.len();
+        if memory_len > self.memory_len {
+            self.memory_expansion_gas += memory_gas(memory_len) - memory_gas(self.memory_len);
+        }
+        self.tracer.step_end(interp, context);
+    }
+
+    fn log(&mut self, context: &mut EvmContext<DB>, log: &Log) {
+        self.tracer.log(context, log);
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.enter_frame();
+        if let Some(four_byte) = &mut self.four_byte {
+            // Geth's 4byteTracer doesn't count calls to precompiles
+            if !context.precompiles.contains(&inputs.bytecode_address) {
+                four_byte.call(context, inputs);
+            }
+        }
+        self.tracer.call(context, inputs)
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit_frame(outcome.result.is_ok(), &outcome.result.gas);
+        self.tracer.call_end(context, inputs, outcome)
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter_frame();
+        self.tracer.create(context, inputs)
+    }
+
+    fn create_end(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit_frame(outcome.result.is_ok(), &outcome.result.gas);
+        self.tracer.create_end(context, inputs, outcome)
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<DB>::selfdestruct(&mut self.tracer, contract, target, value);
+    }
+}
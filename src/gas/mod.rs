@@ -1,8 +1,39 @@
+mod hardfork;
+pub use hardfork::Hardfork;
+mod env;
+pub use env::{BlockConfig, ExecutionEnv};
+mod state;
+pub use state::{apply_state_override, AccountOverride, StateOverride};
+mod geth;
+pub use geth::{geth_trace, GethTracer, GethTracerConfig};
+mod trace;
+pub use trace::{StepTraceOptions, TraceOptions};
+mod source_map;
+pub use source_map::{
+    instruction_indices, parse_source_map, GasHeatMap, LineGas, SourceElement, SourceFile,
+    SourceGasAttribution,
+};
+mod struct_logs;
+pub use struct_logs::{clear_steps, struct_logs, StructLog};
+mod inspector;
+pub use inspector::{ExecutionInspector, TransientWrite};
+mod state_diff;
+pub use state_diff::{AccountDiff, Change, StateDiff};
+mod breakdown;
+pub use breakdown::GasBreakdown;
 mod deploy;
-pub use deploy::deploy;
+pub use deploy::{deploy, deployed_code, Deployment};
 mod transact;
-pub use transact::transact;
+pub use transact::{transact, TransactResult};
+mod result;
+pub use result::{halt_reason, ExecutionResult, ExecutionStatus, EXECUTION_RESULT_VERSION};
 mod execute_calldatas;
 mod execute_calldatas_fork;
-pub use execute_calldatas::{execute_calldatas, Call};
-pub use execute_calldatas_fork::{execute_calldatas_fork, Call as ForkCall, ExecutionResult};
+pub use execute_calldatas::{encode_call, execute_call, execute_calldatas, Call, EncodedCall};
+pub use execute_calldatas_fork::{execute_calldatas_fork, Call as ForkCall};
+mod compare;
+pub use compare::{compare, CallComparison, Comparison, DeploymentComparison, VariantResult};
+mod report;
+pub use report::{gas_report, FunctionGasReport, GasReport};
+#[cfg(test)]
+mod fixtures;
@@ -0,0 +1,109 @@
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Selector;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::ExecutionResult;
+use crate::abi::find_function;
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionGasReport {
+    /// `None` for calls with less than 4 bytes of calldata.
+    pub selector: Option<Selector>,
+    pub function: Option<String>,
+    pub calls: usize,
+    pub reverts: usize,
+    pub min: u64,
+    pub avg: u64,
+    pub median: u64,
+    pub max: u64,
+}
+
+/// Gas usage aggregated per function, in the spirit of `forge test --gas-report`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GasReport {
+    /// `None` when the code was placed directly, as on a fork.
+    pub deployment_gas: Option<u64>,
+    pub code_size: usize,
+    pub functions: Vec<FunctionGasReport>,
+}
+
+/// Groups `results` by the selector of their top-level call, naming each group
+/// from `abi` or, failing that, from the decoded function of its calls.
+pub fn gas_report(
+    results: &[ExecutionResult],
+    abi: Option<&JsonAbi>,
+    deployment_gas: Option<u64>,
+    code_size: usize,
+) -> GasReport {
+    let mut groups: BTreeMap<Option<Selector>, Vec<&ExecutionResult>> = BTreeMap::new();
+    for result in results {
+        let selector = result
+            .traces
+            .nodes()
+            .first()
+            .and_then(|node| node.trace.data.get(..4))
+            .map(Selector::from_slice);
+        groups.entry(selector).or_default().push(result);
+    }
+
+    let functions = groups
+        .into_iter()
+        .map(|(selector, results)| {
+            let function = abi
+                .zip(selector)
+                .and_then(|(abi, selector)| find_function(abi, selector.as_slice()))
+                .map(|function| function.signature())
+                .or_else(|| {
+                    results
+                        .iter()
+                        .find_map(|result| result.decoded.as_ref()?.function.clone())
+                });
+            let mut gas: Vec<u64> = results.iter().map(|result| result.gas_used).collect();
+            let (min, avg, median, max) = stats(&mut gas);
+            FunctionGasReport {
+                selector,
+                function,
+                calls: results.len(),
+                reverts: results.iter().filter(|result| !result.is_success()).count(),
+                min,
+                avg,
+                median,
+                max,
+            }
+        })
+        .collect();
+
+    GasReport {
+        deployment_gas,
+        code_size,
+        functions,
+    }
+}
+
+/// Returns (min, avg, median, max) of a non-empty list of gas values.
+fn stats(gas: &mut [u64]) -> (u64, u64, u64, u64) {
+    gas.sort_unstable();
+    let len = gas.len();
+    let avg = gas.iter().sum::<u64>() / len as u64;
+    let median = if len % 2 == 0 {
+        (gas[len / 2 - 1] + gas[len / 2]) / 2
+    } else {
+        gas[len / 2]
+    };
+    (gas[0], avg, median, gas[len - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_computes_stats() {
+        assert_eq!(stats(&mut [30, 10, 20]), (10, 20, 20, 30));
+        assert_eq!(stats(&mut [40, 10, 20, 30]), (10, 25, 25, 40));
+        assert_eq!(stats(&mut [7]), (7, 7, 7, 7));
+    }
+}
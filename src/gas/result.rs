@@ -0,0 +1,132 @@
+use alloy_primitives::{Address, Bytes, Log};
+use alloy_rpc_types_trace::geth::GethTrace;
+use revm::primitives::{ExecutionResult as RevmExecutionResult, HaltReason, Output};
+use revm_inspectors::tracing::CallTraceArena;
+use serde::Serialize;
+
+use super::{GasBreakdown, StateDiff, StructLog};
+use crate::abi::DecodedResult;
+
+/// Bumped whenever `ExecutionResult` changes in a way clients need to know about.
+pub const EXECUTION_RESULT_VERSION: u32 = 1;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExecutionStatus {
+    Success,
+    Revert,
+    Halt,
+}
+
+/// Result of a single call, shared by the local and fork execution paths.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResult {
+    pub version: u32,
+    pub status: ExecutionStatus,
+    pub gas_used: u64,
+    pub gas_refunded: u64,
+    pub gas_breakdown: Option<GasBreakdown>,
+    pub output: Bytes,
+    pub logs: Vec<Log>,
+    pub traces: CallTraceArena,
+    pub created_address: Option<Address>,
+    pub halt_reason: Option<String>,
+    pub decoded: Option<DecodedResult>,
+    pub state_diff: Option<StateDiff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub struct_logs: Option<Vec<StructLog>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geth_trace: Option<GethTrace>,
+}
+
+impl ExecutionResult {
+    pub fn from_revm(result: RevmExecutionResult, traces: CallTraceArena) -> Self {
+        let mut unified = Self {
+            version: EXECUTION_RESULT_VERSION,
+            status: ExecutionStatus::Success,
+            gas_used: result.gas_used(),
+            gas_refunded: 0,
+            gas_breakdown: None,
+            output: Bytes::new(),
+            logs: vec![],
+            traces,
+            created_address: None,
+            halt_reason: None,
+            decoded: None,
+            state_diff: None,
+            struct_logs: None,
+            geth_trace: None,
+        };
+
+        match result {
+            RevmExecutionResult::Success {
+                gas_refunded,
+                logs,
+                output,
+                ..
+            } => {
+                unified.gas_refunded = gas_refunded;
+                unified.logs = logs;
+                match output {
+                    Output::Call(data) => unified.output = data,
+                    Output::Create(data, address) => {
+                        unified.output = data;
+                        unified.created_address = address;
+                    }
+                }
+            }
+            RevmExecutionResult::Revert { output, .. } => {
+                unified.status = ExecutionStatus::Revert;
+                unified.output = output;
+            }
+            RevmExecutionResult::Halt { reason, .. } => {
+                unified.status = ExecutionStatus::Halt;
+                unified.halt_reason = Some(halt_reason(reason));
+            }
+        }
+
+        unified
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status == ExecutionStatus::Success
+    }
+}
+
+/// Names a halt the same way on both execution paths. The fork path only gets
+/// the interpreter's `InstructionResult` back from forge, so it converts that
+/// to a `HaltReason` first.
+pub fn halt_reason(reason: HaltReason) -> String {
+    format!("{:?}", reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        execute_calldatas, fixtures, Call, ExecutionEnv, StateOverride, TraceOptions,
+    };
+    use revm::interpreter::{InstructionResult, SuccessOrHalt};
+
+    #[test]
+    fn test_halt_reason_matches_fork_naming() {
+        // INVALID
+        let (_, results) = execute_calldatas(
+            fixtures::initcode(&[0xfe]),
+            vec![Call::default()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        let from_fork = SuccessOrHalt::from(InstructionResult::InvalidFEOpcode)
+            .to_halt()
+            .map(halt_reason);
+        assert_eq!(results[0].status, ExecutionStatus::Halt);
+        assert_eq!(results[0].halt_reason, from_fork);
+        assert_eq!(from_fork.as_deref(), Some("InvalidFEOpcode"));
+    }
+}
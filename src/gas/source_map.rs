@@ -0,0 +1,212 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::StructLog;
+
+/// One entry of a solc source map (`s:l:f:j:m`), with omitted fields already
+/// inherited from the previous entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceElement {
+    pub offset: usize,
+    pub length: usize,
+    /// `None` for compiler-generated code (`-1`).
+    pub file: Option<u32>,
+}
+
+/// Parses a compressed solc source map into one element per instruction.
+pub fn parse_source_map(source_map: &str) -> Vec<SourceElement> {
+    let mut elements = vec![];
+    let (mut offset, mut length, mut file) = (0i64, 0i64, -1i64);
+
+    for entry in source_map.split(';') {
+        let mut fields = entry.split(':');
+        if let Some(value) = fields.next().and_then(|field| field.parse().ok()) {
+            offset = value;
+        }
+        if let Some(value) = fields.next().and_then(|field| field.parse().ok()) {
+            length = value;
+        }
+        if let Some(value) = fields.next().and_then(|field| field.parse().ok()) {
+            file = value;
+        }
+        elements.push(SourceElement {
+            offset: offset.max(0) as usize,
+            length: length.max(0) as usize,
+            file: u32::try_from(file).ok(),
+        });
+    }
+    elements
+}
+
+/// Maps every byte of `code` to the index of the instruction it belongs to,
+/// which is what source map entries are indexed by.
+pub fn instruction_indices(code: &[u8]) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(code.len());
+    let mut pc = 0;
+    let mut instruction = 0;
+    while pc < code.len() {
+        // PUSH1..PUSH32 carry their immediate in the following bytes
+        let size = match code[pc] {
+            op @ 0x60..=0x7f => 1 + (op - 0x5f) as usize,
+            _ => 1,
+        };
+        for _ in 0..size.min(code.len() - pc) {
+            indices.push(instruction);
+        }
+        pc += size;
+        instruction += 1;
+    }
+    indices
+}
+
+/// A source file the source map's file indices refer to.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub id: u32,
+    pub name: String,
+    pub content: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LineGas {
+    pub file: String,
+    /// 1-based, like editors count them.
+    pub line: usize,
+    pub gas: u64,
+    /// Number of executed opcodes mapped to this line.
+    pub steps: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GasHeatMap {
+    pub lines: Vec<LineGas>,
+    /// Gas of opcodes the compiler doesn't map to any source, such as the
+    /// function dispatcher.
+    pub unattributed: u64,
+}
+
+/// Accumulates the gas of step traces onto the source lines of one contract's
+/// runtime code.
+pub struct SourceGasAttribution {
+    instructions: Vec<usize>,
+    elements: Vec<SourceElement>,
+    /// Per source id: its name and the byte offset every line starts at.
+    sources: BTreeMap<u32, (String, Vec<usize>)>,
+    lines: BTreeMap<(u32, usize), (u64, usize)>,
+    unattributed: u64,
+}
+
+impl SourceGasAttribution {
+    pub fn new(code: &[u8], source_map: &str, sources: &[SourceFile]) -> Self {
+        let sources = sources
+            .iter()
+            .map(|source| {
+                let line_starts = std::iter::once(0)
+                    .chain(source.content.match_indices('\n').map(|(i, _)| i + 1))
+                    .collect();
+                (source.id, (source.name.clone(), line_starts))
+            })
+            .collect();
+        Self {
+            instructions: instruction_indices(code),
+            elements: parse_source_map(source_map),
+            sources,
+            lines: BTreeMap::new(),
+            unattributed: 0,
+        }
+    }
+
+    /// Adds the top-level frame of a call's structLogs. A step is charged the
+    /// gas spent until the frame's next step, so calls into other contracts
+    /// are attributed to the line that made them.
+    pub fn add(&mut self, logs: &[StructLog]) {
+        let Some(depth) = logs.iter().map(|log| log.depth).min() else {
+            return;
+        };
+        let frame: Vec<_> = logs.iter().filter(|log| log.depth == depth).collect();
+
+        for (i, log) in frame.iter().enumerate() {
+            let gas = match frame.get(i + 1) {
+                Some(next) => log.gas.saturating_sub(next.gas),
+                None => log.gas_cost,
+            };
+            match self.line_of(log.pc as usize) {
+                Some(key) => {
+                    let entry = self.lines.entry(key).or_default();
+                    entry.0 += gas;
+                    entry.1 += 1;
+                }
+                None => self.unattributed += gas,
+            }
+        }
+    }
+
+    pub fn finish(self) -> GasHeatMap {
+        let lines = self
+            .lines
+            .into_iter()
+            .map(|((file, line), (gas, steps))| LineGas {
+                file: self.sources[&file].0.clone(),
+                line: line + 1,
+                gas,
+                steps,
+            })
+            .collect();
+        GasHeatMap {
+            lines,
+            unattributed: self.unattributed,
+        }
+    }
+
+    fn line_of(&self, pc: usize) -> Option<(u32, usize)> {
+        let element = self.elements.get(*self.instructions.get(pc)?)?;
+        let file = element.file?;
+        let (_, line_starts) = self.sources.get(&file)?;
+        let line = line_starts.partition_point(|start| *start <= element.offset) - 1;
+        Some((file, line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_compressed_source_map() {
+        let elements = parse_source_map("10:5:0;:3;;20::-1");
+        assert_eq!(
+            elements,
+            vec![
+                SourceElement {
+                    offset: 10,
+                    length: 5,
+                    file: Some(0)
+                },
+                SourceElement {
+                    offset: 10,
+                    length: 3,
+                    file: Some(0)
+                },
+                SourceElement {
+                    offset: 10,
+                    length: 3,
+                    file: Some(0)
+                },
+                SourceElement {
+                    offset: 20,
+                    length: 3,
+                    file: None
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_maps_push_data_to_its_instruction() {
+        // PUSH1 0x80 PUSH2 0x0000 STOP
+        let code = [0x60, 0x80, 0x61, 0x00, 0x00, 0x00];
+        assert_eq!(instruction_indices(&code), vec![0, 0, 1, 1, 1, 2]);
+    }
+}
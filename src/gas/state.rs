@@ -0,0 +1,139 @@
+use alloy_primitives::{Address, Bytes, U256};
+use revm::{
+    db::CacheDB,
+    primitives::{AccountInfo, Bytecode},
+    InMemoryDB,
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Account fields to set before execution, like one entry of geth's state
+/// override object. Storage slots are merged into whatever is already there.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Bytes>,
+    #[serde(default)]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
+
+pub fn apply_state_override(
+    state: &StateOverride,
+    db: &mut CacheDB<InMemoryDB>,
+) -> Result<(), eyre::Error> {
+    for (address, account) in state {
+        let mut info: AccountInfo = db.load_account(*address)?.info.clone();
+        if let Some(balance) = account.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account.nonce {
+            info.nonce = nonce;
+        }
+        if let Some(code) = &account.code {
+            let code = Bytecode::new_raw(code.clone());
+            info.code_hash = code.hash_slow();
+            info.code = Some(code);
+        }
+        db.insert_account_info(*address, info);
+
+        for (slot, value) in &account.storage {
+            db.insert_account_storage(*address, *slot, *value)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        execute_calldatas, fixtures, Call, ExecutionEnv, ExecutionStatus, TraceOptions,
+    };
+    use revm::DatabaseRef;
+
+    #[test]
+    fn test_override_merges_into_existing_account() {
+        let address = Address::repeat_byte(0x11);
+        let mut db = CacheDB::new(InMemoryDB::default());
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                balance: U256::from(10),
+                nonce: 3,
+                ..Default::default()
+            },
+        );
+        db.insert_account_storage(address, U256::from(1), U256::from(1))
+            .unwrap();
+        db.insert_account_storage(address, U256::from(2), U256::from(2))
+            .unwrap();
+
+        let state = StateOverride::from([(
+            address,
+            AccountOverride {
+                nonce: Some(7),
+                storage: BTreeMap::from([
+                    (U256::from(2), U256::from(20)),
+                    (U256::from(3), U256::from(30)),
+                ]),
+                ..Default::default()
+            },
+        )]);
+        apply_state_override(&state, &mut db).unwrap();
+
+        let info = db.basic_ref(address).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(10));
+        assert_eq!(info.nonce, 7);
+        let slot = |slot: u64| db.storage_ref(address, U256::from(slot)).unwrap();
+        assert_eq!(slot(1), U256::from(1));
+        assert_eq!(slot(2), U256::from(20));
+        assert_eq!(slot(3), U256::from(30));
+    }
+
+    #[test]
+    fn test_overrides_apply_before_deploy() {
+        let caller = Address::repeat_byte(0x22);
+        let state = StateOverride::from([
+            (
+                Address::ZERO,
+                AccountOverride {
+                    nonce: Some(5),
+                    ..Default::default()
+                },
+            ),
+            (
+                caller,
+                AccountOverride {
+                    balance: Some(U256::from(1000)),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        // Returns SELFBALANCE
+        let bytecode = fixtures::initcode(&[0x47, 0x5f, 0x52, 0x60, 0x20, 0x5f, 0xf3]);
+        let call = Call {
+            caller: Some(caller),
+            value: Some(U256::from(300)),
+            ..Default::default()
+        };
+
+        let (deployment, results) = execute_calldatas(
+            bytecode,
+            vec![call],
+            None,
+            ExecutionEnv::default(),
+            &state,
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        // The deployer's nonce was overridden before its create
+        assert_eq!(deployment.address, Address::ZERO.create(5));
+        // and the caller could only send value because of its balance override
+        assert_eq!(results[0].status, ExecutionStatus::Success);
+        assert_eq!(U256::from_be_slice(&results[0].output), U256::from(300));
+    }
+}
@@ -0,0 +1,170 @@
+use alloy_primitives::{Address, Bytes, U256};
+use revm::{db::CacheDB, primitives::EvmState, DatabaseRef, InMemoryDB};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::TransientWrite;
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    pub created: bool,
+    pub selfdestructed: bool,
+    pub balance: Option<Change<U256>>,
+    pub nonce: Option<Change<u64>>,
+    /// Runtime code now at the address, when it changed.
+    pub code: Option<Bytes>,
+    pub storage: BTreeMap<U256, Change<U256>>,
+}
+
+/// What a single call changed, computed before its state is committed.
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiff {
+    pub accounts: BTreeMap<Address, AccountDiff>,
+    pub transient_storage: Vec<TransientWrite>,
+}
+
+impl StateDiff {
+    pub fn new(
+        state: &EvmState,
+        db: &CacheDB<InMemoryDB>,
+        transient_storage: Vec<TransientWrite>,
+    ) -> Result<Self, eyre::Error> {
+        let mut accounts = BTreeMap::new();
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue;
+            }
+            let before = db.basic_ref(*address)?.unwrap_or_default();
+
+            let diff = AccountDiff {
+                created: account.is_created(),
+                selfdestructed: account.is_selfdestructed(),
+                balance: (before.balance != account.info.balance).then(|| Change {
+                    from: before.balance,
+                    to: account.info.balance,
+                }),
+                nonce: (before.nonce != account.info.nonce).then(|| Change {
+                    from: before.nonce,
+                    to: account.info.nonce,
+                }),
+                code: (before.code_hash != account.info.code_hash)
+                    .then(|| account.info.code.as_ref().map(|code| code.original_bytes()))
+                    .flatten(),
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, slot)| slot.is_changed())
+                    .map(|(key, slot)| {
+                        (
+                            *key,
+                            Change {
+                                from: slot.original_value(),
+                                to: slot.present_value(),
+                            },
+                        )
+                    })
+                    .collect(),
+            };
+
+            let changed = diff.created
+                || diff.selfdestructed
+                || diff.balance.is_some()
+                || diff.nonce.is_some()
+                || diff.code.is_some()
+                || !diff.storage.is_empty();
+            if changed {
+                accounts.insert(*address, diff);
+            }
+        }
+
+        Ok(Self {
+            accounts,
+            transient_storage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{
+        execute_calldatas, fixtures, Call, ExecutionEnv, StateOverride, TraceOptions,
+    };
+
+    #[test]
+    fn test_storage_writes_are_diffed_per_call() {
+        let (deployment, results) = execute_calldatas(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7), fixtures::set(9), fixtures::get()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+        let diffs: Vec<_> = results
+            .iter()
+            .map(|result| result.state_diff.as_ref().unwrap())
+            .collect();
+
+        let slot =
+            |diff: &StateDiff| diff.accounts[&deployment.address].storage[&U256::ZERO].clone();
+        assert_eq!(
+            slot(diffs[0]),
+            Change {
+                from: U256::ZERO,
+                to: U256::from(7)
+            }
+        );
+        assert_eq!(
+            slot(diffs[1]),
+            Change {
+                from: U256::from(7),
+                to: U256::from(9)
+            }
+        );
+        assert!(!diffs[0].accounts[&deployment.address].created);
+
+        // A read changes nothing on the contract, only the caller's nonce
+        assert!(!diffs[2].accounts.contains_key(&deployment.address));
+        let caller = &diffs[2].accounts[&Address::ZERO];
+        assert!(caller.nonce.is_some());
+        assert!(caller.storage.is_empty());
+    }
+
+    #[test]
+    fn test_transient_writes_are_reported() {
+        // PUSH1 42 PUSH1 1 TSTORE STOP
+        let bytecode = fixtures::initcode(&[0x60, 0x2a, 0x60, 0x01, 0x5d, 0x00]);
+        let (deployment, results) = execute_calldatas(
+            bytecode,
+            vec![Call::default()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        let diff = results[0].state_diff.as_ref().unwrap();
+        let [write] = &diff.transient_storage[..] else {
+            panic!(
+                "expected one transient write, got {:?}",
+                diff.transient_storage
+            );
+        };
+        assert_eq!(write.address, deployment.address);
+        assert_eq!(write.slot, U256::from(1));
+        assert_eq!(write.value, U256::from(42));
+        // Transient storage never shows up as persistent storage
+        assert!(!diff.accounts.contains_key(&deployment.address));
+    }
+}
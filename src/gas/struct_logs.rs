@@ -0,0 +1,190 @@
+use alloy_primitives::{hex, Address, B256, U256};
+use revm::interpreter::opcode;
+use revm_inspectors::tracing::CallTraceArena;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+use super::StepTraceOptions;
+
+/// One executed opcode, in geth's `debug_traceTransaction` structLog format.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u64,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: u64,
+    pub refund: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, B256>>,
+}
+
+/// Flattens the recorded steps of every call frame into execution order.
+pub fn struct_logs(traces: &CallTraceArena, options: &StepTraceOptions) -> Vec<StructLog> {
+    let mut logs = vec![];
+    let mut storage = HashMap::new();
+    if !traces.nodes().is_empty() {
+        collect(traces, 0, options, &mut storage, &mut logs);
+    }
+    logs
+}
+
+/// Removes recorded steps from the call tree once they've been turned into
+/// structLogs, so they aren't returned twice.
+pub fn clear_steps(traces: &mut CallTraceArena) {
+    for node in traces.nodes_mut() {
+        node.trace.steps.clear();
+    }
+}
+
+fn collect(
+    traces: &CallTraceArena,
+    idx: usize,
+    options: &StepTraceOptions,
+    storage: &mut HashMap<Address, BTreeMap<B256, B256>>,
+    logs: &mut Vec<StructLog>,
+) {
+    let node = &traces.nodes()[idx];
+    let mut children = node.children.iter();
+    let steps = &node.trace.steps;
+
+    for (i, step) in steps.iter().enumerate() {
+        let op = step.op.get();
+        let stack = step.stack.as_deref().unwrap_or_default();
+
+        let storage_entry = if options.disable_storage {
+            None
+        } else {
+            // Geth reports a contract's storage as seen so far, on the
+            // SLOAD/SSTORE steps that touch it.
+            let touched = match op {
+                opcode::SLOAD => stack.last().zip(
+                    steps
+                        .get(i + 1)
+                        .and_then(|next| next.stack.as_ref()?.last().copied()),
+                ),
+                opcode::SSTORE => stack.last().zip(stack.iter().rev().nth(1).copied()),
+                _ => None,
+            };
+            touched.map(|(slot, value)| {
+                let contract_storage = storage.entry(step.contract).or_default();
+                contract_storage.insert(B256::from(*slot), B256::from(value));
+                contract_storage.clone()
+            })
+        };
+
+        logs.push(StructLog {
+            pc: step.pc as u64,
+            op: step.op.as_str().to_string(),
+            gas: step.gas_remaining,
+            gas_cost: step.gas_cost,
+            depth: step.depth,
+            refund: step.gas_refund_counter,
+            stack: (!options.disable_stack).then(|| stack.to_vec()),
+            memory: options
+                .enable_memory
+                .then(|| {
+                    step.memory
+                        .as_ref()
+                        .map(|memory| memory.as_bytes().chunks(32).map(hex::encode).collect())
+                })
+                .flatten(),
+            storage: storage_entry,
+        });
+
+        if is_call_like(op) {
+            if let Some(child) = children.next() {
+                collect(traces, *child, options, storage, logs);
+            }
+        }
+    }
+}
+
+fn is_call_like(op: u8) -> bool {
+    matches!(
+        op,
+        opcode::CALL
+            | opcode::CALLCODE
+            | opcode::DELEGATECALL
+            | opcode::STATICCALL
+            | opcode::CREATE
+            | opcode::CREATE2
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{execute_calldatas, fixtures, ExecutionEnv, StateOverride, TraceOptions};
+    use serde_json::json;
+
+    fn run(options: StepTraceOptions) -> Vec<Vec<StructLog>> {
+        let trace = TraceOptions {
+            step_trace: Some(options),
+            ..Default::default()
+        };
+        let (_, results) = execute_calldatas(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7), fixtures::get()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &trace,
+        )
+        .unwrap();
+        results
+            .into_iter()
+            .map(|result| result.struct_logs.unwrap())
+            .collect()
+    }
+
+    fn find<'a>(logs: &'a [StructLog], op: &str) -> &'a StructLog {
+        logs.iter().find(|log| log.op == op).unwrap()
+    }
+
+    #[test]
+    fn test_storage_snapshot_on_sload_and_sstore() {
+        let runs = run(StepTraceOptions::default());
+        let expected = BTreeMap::from([(B256::ZERO, B256::from(U256::from(7)))]);
+
+        assert_eq!(find(&runs[0], "SSTORE").storage.as_ref(), Some(&expected));
+        // SLOAD's value is only on the stack after it ran
+        assert_eq!(find(&runs[1], "SLOAD").storage.as_ref(), Some(&expected));
+        assert!(find(&runs[1], "MSTORE").storage.is_none());
+        assert!(find(&runs[1], "MSTORE").stack.is_some());
+        assert!(runs[1].iter().all(|log| log.memory.is_none()));
+    }
+
+    #[test]
+    fn test_step_trace_opt_outs() {
+        let options: StepTraceOptions = serde_json::from_value(json!({
+            "enableMemory": true,
+            "disableStack": true,
+            "disableStorage": true,
+        }))
+        .unwrap();
+        let runs = run(options);
+
+        for logs in &runs {
+            assert!(logs.iter().all(|log| log.stack.is_none()));
+            assert!(logs.iter().all(|log| log.storage.is_none()));
+        }
+        // Memory is recorded before each step, so it's there once MSTORE ran
+        assert!(find(&runs[1], "RETURN").memory.is_some());
+    }
+
+    #[test]
+    fn test_storage_without_stack() {
+        let runs = run(StepTraceOptions {
+            disable_stack: true,
+            ..Default::default()
+        });
+        assert!(runs[0].iter().all(|log| log.stack.is_none()));
+        assert!(find(&runs[0], "SSTORE").storage.is_some());
+    }
+}
@@ -0,0 +1,56 @@
+use revm_inspectors::tracing::{StackSnapshotType, TracingInspectorConfig};
+use serde::Deserialize;
+
+use super::{GethTracer, GethTracerConfig};
+
+/// Opt-in tracing beyond the call tree every execution returns.
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct TraceOptions {
+    pub step_trace: Option<StepTraceOptions>,
+    /// Geth tracer to emit alongside the native trace.
+    pub tracer: Option<GethTracer>,
+    #[serde(default)]
+    pub tracer_config: GethTracerConfig,
+}
+
+/// What to include in each structLog entry, named after geth's logger config.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StepTraceOptions {
+    #[serde(default)]
+    pub enable_memory: bool,
+    #[serde(default)]
+    pub disable_stack: bool,
+    #[serde(default)]
+    pub disable_storage: bool,
+}
+
+impl TraceOptions {
+    pub fn wants_prestate(&self) -> bool {
+        self.tracer == Some(GethTracer::PrestateTracer)
+    }
+
+    pub fn tracing_config(&self) -> TracingInspectorConfig {
+        let config = TracingInspectorConfig {
+            record_logs: true,
+            ..TracingInspectorConfig::default_parity()
+        };
+        let Some(step_trace) = &self.step_trace else {
+            return config;
+        };
+
+        // Storage is reconstructed from SLOAD/SSTORE operands, so it needs the
+        // stack even when the stack itself isn't returned.
+        let record_stack = !step_trace.disable_stack || !step_trace.disable_storage;
+        TracingInspectorConfig {
+            record_steps: true,
+            record_memory_snapshots: step_trace.enable_memory,
+            record_stack_snapshots: if record_stack {
+                StackSnapshotType::Full
+            } else {
+                StackSnapshotType::None
+            },
+            ..config
+        }
+    }
+}
@@ -1,17 +1,37 @@
 use alloy_primitives::{Address, Bytes, U256};
+use alloy_rpc_types_trace::geth::GethTrace;
 use revm::{
     db::CacheDB,
-    primitives::{ExecutionResult, TransactTo, TxEnv},
-    Evm, InMemoryDB,
+    inspector_handle_register,
+    primitives::{ExecutionResult, ResultAndState, TransactTo, TxEnv},
+    DatabaseCommit, Evm, InMemoryDB,
 };
+use revm_inspectors::tracing::{CallTraceArena, FourByteInspector};
+
+use super::{
+    clear_steps, geth_trace, struct_logs, ExecutionEnv, ExecutionInspector, GasBreakdown,
+    GethTracer, StateDiff, StructLog, TraceOptions,
+};
+
+#[derive(Debug)]
+pub struct TransactResult {
+    pub result: ExecutionResult,
+    pub traces: CallTraceArena,
+    pub state_diff: StateDiff,
+    pub struct_logs: Option<Vec<StructLog>>,
+    pub geth_trace: Option<GethTrace>,
+    pub gas_breakdown: GasBreakdown,
+}
 
 pub fn transact(
     transact_to: Address,
     calldata: Option<Bytes>,
     value: Option<U256>,
     caller: Option<Address>,
+    env: &ExecutionEnv,
+    trace: &TraceOptions,
     db: &mut CacheDB<InMemoryDB>,
-) -> Result<ExecutionResult, eyre::Error> {
+) -> Result<TransactResult, eyre::Error> {
     let mut tx = TxEnv::default();
     tx.transact_to = TransactTo::Call(transact_to);
     if let Some(calldata) = calldata {
@@ -25,9 +45,126 @@ pub fn transact(
     if let Some(value) = value {
         tx.value = value;
     }
-    let mut evm = Evm::builder().with_db(db).with_tx_env(tx).build();
 
-    let tx_res = evm.transact_commit()?;
+    let mut inspector = ExecutionInspector::new(trace.tracing_config());
+    if trace.tracer == Some(GethTracer::FourByteTracer) {
+        inspector.four_byte = Some(FourByteInspector::default());
+    }
+    let mut evm = Evm::builder()
+        .with_db(&mut *db)
+        .with_external_context(&mut inspector)
+        .with_spec_id(env.spec_id)
+        .with_block_env(env.block.clone())
+        .modify_cfg_env(|cfg| *cfg = env.cfg.clone())
+        .with_tx_env(tx)
+        .append_handler_register(inspector_handle_register)
+        .build();
 
-    Ok(tx_res)
+    let result_and_state = evm.transact()?;
+    let result = &result_and_state.result;
+    let refund = match result {
+        ExecutionResult::Success { gas_refunded, .. } => *gas_refunded,
+        _ => 0,
+    };
+    let tx = evm.tx().clone();
+    drop(evm);
+    let mut gas_breakdown = GasBreakdown::new(
+        &tx,
+        false,
+        env.spec_id,
+        Some(inspector.execution_gas),
+        result.gas_used(),
+        refund,
+    );
+    gas_breakdown.memory_expansion = Some(inspector.memory_expansion_gas);
+    gas_breakdown.refund_uncapped = Some(inspector.uncapped_refund);
+
+    let state_diff = StateDiff::new(&result_and_state.state, db, inspector.transient_writes)?;
+    let geth_trace = trace
+        .tracer
+        .map(|tracer| {
+            geth_trace(
+                tracer,
+                &trace.tracer_config,
+                &inspector.tracer.clone().into_geth_builder(),
+                inspector.four_byte.take(),
+                tx.gas_limit,
+                &result_and_state,
+                &*db,
+            )
+        })
+        .transpose()?;
+    let ResultAndState { result, state } = result_and_state;
+    db.commit(state);
+
+    let mut traces = inspector.tracer.into_traces();
+    let struct_logs = trace.step_trace.as_ref().map(|options| {
+        let logs = struct_logs(&traces, options);
+        clear_steps(&mut traces);
+        logs
+    });
+
+    Ok(TransactResult {
+        result,
+        traces,
+        state_diff,
+        struct_logs,
+        geth_trace,
+        gas_breakdown,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gas::{execute_calldatas, fixtures, StateOverride, StepTraceOptions};
+
+    #[test]
+    fn test_local_calls_are_traced() {
+        let (deployment, results) = execute_calldatas(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7), fixtures::get()],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &TraceOptions::default(),
+        )
+        .unwrap();
+
+        let set = &results[0].traces.nodes()[0];
+        assert_eq!(set.trace.address, deployment.address);
+        assert!(set.trace.success);
+        assert_eq!(set.logs.len(), 1);
+        assert!(set.trace.steps.is_empty());
+        assert!(results[0].struct_logs.is_none());
+
+        let get = &results[1].traces.nodes()[0];
+        assert_eq!(get.trace.output, results[1].output);
+        assert_eq!(get.trace.output[31], 7);
+    }
+
+    #[test]
+    fn test_step_trace_is_returned_as_struct_logs() {
+        let trace = TraceOptions {
+            step_trace: Some(StepTraceOptions::default()),
+            ..Default::default()
+        };
+        let (_, results) = execute_calldatas(
+            fixtures::simple_storage(),
+            vec![fixtures::set(7)],
+            None,
+            ExecutionEnv::default(),
+            &StateOverride::default(),
+            &trace,
+        )
+        .unwrap();
+
+        let logs = results[0].struct_logs.as_ref().unwrap();
+        assert_eq!(logs[0].pc, 0);
+        assert_eq!(logs[0].op, "PUSH1");
+        assert!(logs.iter().any(|log| log.op == "SSTORE"));
+        assert_eq!(logs.last().unwrap().op, "STOP");
+        // Steps are only returned once, as structLogs
+        assert!(results[0].traces.nodes()[0].trace.steps.is_empty());
+    }
 }
@@ -1,4 +1,7 @@
+pub mod abi;
+pub mod bytecode;
 pub mod byteracing;
 pub mod compile;
 pub mod gas;
 pub mod routes;
+pub mod session;
@@ -0,0 +1,24 @@
+use crate::{
+    bytecode::{assemble, Assembled},
+    gas::Hardfork,
+};
+use revm::primitives::SpecId;
+use rocket::{post, response::status, serde::json::Json};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AssembleRequest {
+    pub source: String,
+    /// Decides whether `PUSH 0` can use PUSH0, defaults to the latest.
+    pub hardfork: Option<Hardfork>,
+}
+
+#[post("/assemble", format = "json", data = "<req>")]
+pub fn assemble_route(
+    req: Json<AssembleRequest>,
+) -> Result<Json<Assembled>, status::BadRequest<Option<String>>> {
+    let spec_id = req.hardfork.map_or(SpecId::LATEST, SpecId::from);
+    let assembled =
+        assemble(&req.source, spec_id).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(assembled))
+}
@@ -0,0 +1,57 @@
+use crate::{
+    abi::append_constructor_args,
+    gas::{compare, BlockConfig, Call, Comparison, ExecutionEnv, Hardfork, StateOverride},
+};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::hex;
+use revm::primitives::Bytecode;
+use rocket::{post, response::status, serde::json::Json};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct CompareRequest {
+    /// Initcode to compare, the first one being the baseline.
+    pub bytecodes: Vec<String>,
+    pub calls: Vec<Call>,
+    /// Shared by every bytecode, which are expected to have the same interface.
+    pub abi: Option<JsonAbi>,
+    pub constructor_args: Option<Vec<Value>>,
+    #[serde(default)]
+    pub block: BlockConfig,
+    pub hardfork: Option<Hardfork>,
+    #[serde(default)]
+    pub state: StateOverride,
+}
+
+#[post("/compare", format = "json", data = "<req>")]
+pub fn compare_route(
+    req: Json<CompareRequest>,
+) -> Result<Json<Comparison>, status::BadRequest<Option<String>>> {
+    let result = handle(req).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle(req: Json<CompareRequest>) -> Result<Comparison, eyre::Error> {
+    let bytecodes = req
+        .bytecodes
+        .iter()
+        .map(|bytecode| {
+            let bytecode = hex::decode(bytecode).map_err(|err| eyre::eyre!(err.to_string()))?;
+            let initcode = append_constructor_args(
+                bytecode,
+                req.abi.as_ref(),
+                req.constructor_args.as_deref(),
+            )?;
+            Ok(Bytecode::new_raw(initcode))
+        })
+        .collect::<Result<Vec<_>, eyre::Error>>()?;
+
+    compare(
+        bytecodes,
+        req.calls.clone(),
+        req.abi.as_ref(),
+        ExecutionEnv::new(&req.block, req.hardfork),
+        &req.state,
+    )
+}
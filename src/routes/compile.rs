@@ -0,0 +1,14 @@
+use super::compile_solidity::CompileRequest;
+use crate::compile::solidity::{compile, CompileResult};
+use rocket::{post, response::status, serde::json::Json};
+
+/// Compiles any mix of Solidity and Vyper (`.vy`) files. Artifacts have the same
+/// shape for both, so either can be passed on to the execute endpoints.
+#[post("/compile", format = "json", data = "<req>")]
+pub fn compile_route(
+    req: Json<CompileRequest>,
+) -> Result<Json<CompileResult>, status::BadRequest<Option<String>>> {
+    let result = compile(&req.files, &req.settings)
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
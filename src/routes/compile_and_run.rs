@@ -0,0 +1,148 @@
+use crate::{
+    compile::{
+        settings::CompileSettings,
+        solidity::{compile, SolidityFile},
+    },
+    gas::{
+        execute_calldatas, BlockConfig, Call, Deployment, ExecutionEnv, ExecutionResult, Hardfork,
+        StateOverride, TraceOptions,
+    },
+};
+use foundry_compilers::multi::MultiCompilerError;
+use revm::primitives::Bytecode;
+use rocket::{post, response::status, serde::json::Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct CompileAndRunRequest {
+    pub files: Vec<SolidityFile>,
+    #[serde(default)]
+    pub settings: CompileSettings,
+    /// Contract to deploy, as `Name` or `File.sol:Name`.
+    pub contract: String,
+    pub constructor_args: Option<Vec<Value>>,
+    pub calls: Vec<Call>,
+    #[serde(default)]
+    pub block: BlockConfig,
+    pub hardfork: Option<Hardfork>,
+    #[serde(default)]
+    pub state: StateOverride,
+    #[serde(flatten)]
+    pub trace: TraceOptions,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompileAndRunResponse {
+    /// Compiler errors and warnings. Nothing is executed if any are errors.
+    pub errors: Vec<MultiCompilerError>,
+    pub deployment: Option<Deployment>,
+    pub results: Vec<ExecutionResult>,
+}
+
+#[post("/compile_and_run", format = "json", data = "<req>")]
+pub fn compile_and_run_route(
+    req: Json<CompileAndRunRequest>,
+) -> Result<Json<CompileAndRunResponse>, status::BadRequest<Option<String>>> {
+    let result = handle(req).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle(req: Json<CompileAndRunRequest>) -> Result<CompileAndRunResponse, eyre::Error> {
+    let output = compile(&req.files, &req.settings)?;
+    if output.has_errors() {
+        return Ok(CompileAndRunResponse {
+            errors: output.errors,
+            deployment: None,
+            results: vec![],
+        });
+    }
+
+    let contract = output.find_contract(&req.contract)?;
+    let initcode = contract.initcode_with_args(req.constructor_args.as_deref())?;
+    let (deployment, results) = execute_calldatas(
+        Bytecode::new_raw(initcode),
+        req.calls.clone(),
+        contract.abi(),
+        ExecutionEnv::new(&req.block, req.hardfork),
+        &req.state,
+        &req.trace,
+    )?;
+
+    Ok(CompileAndRunResponse {
+        errors: output.errors.clone(),
+        deployment: Some(deployment),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const COUNTER: &str = r#"
+        pragma solidity ^0.8.0;
+
+        contract Counter {
+            uint256 public count;
+            event Bumped(uint256 count);
+
+            constructor(uint256 start) {
+                count = start;
+            }
+
+            function bump(uint256 by) public {
+                count += by;
+                emit Bumped(count);
+            }
+        }
+
+        contract Other {}
+    "#;
+
+    fn request(contract: &str, content: &str) -> Json<CompileAndRunRequest> {
+        Json(
+            serde_json::from_value(json!({
+                "files": [{ "name": "Counter.sol", "content": content }],
+                "contract": contract,
+                "constructor_args": ["10"],
+                "calls": [
+                    { "function": "bump", "args": ["5"] },
+                    { "function": "count" },
+                ],
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_compile_and_run_by_contract_name() {
+        for contract in ["Counter", "Counter.sol:Counter"] {
+            let response = handle(request(contract, COUNTER)).unwrap();
+            assert!(response.deployment.is_some());
+
+            let bump = response.results[0].decoded.as_ref().unwrap();
+            assert_eq!(bump.function.as_deref(), Some("bump(uint256)"));
+            assert_eq!(bump.logs[0].event, "Bumped(uint256)");
+            assert_eq!(bump.logs[0].params[0].value, json!("15"));
+
+            let count = response.results[1].decoded.as_ref().unwrap();
+            assert_eq!(count.returns.as_ref().unwrap()[0].value, json!("15"));
+        }
+    }
+
+    #[test]
+    fn test_compile_errors_skip_execution() {
+        let response = handle(request("Counter", "contract Counter {")).unwrap();
+        assert!(!response.errors.is_empty());
+        assert!(response.deployment.is_none());
+        assert!(response.results.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_contract_name() {
+        assert!(handle(request("Missing", COUNTER)).is_err());
+    }
+}
@@ -1,16 +1,30 @@
-use crate::compile::solidity::{compile, CompileResult, SolidityFile};
+use crate::compile::{
+    settings::CompileSettings,
+    solidity::{compile, CompileResult, SolidityFile},
+    vyper::is_vyper,
+};
 use rocket::{post, response::status, serde::json::Json};
 use serde::Deserialize;
 
 #[derive(Deserialize)]
 pub struct CompileRequest {
     pub files: Vec<SolidityFile>,
+    #[serde(default)]
+    pub settings: CompileSettings,
 }
+/// Compiles Solidity files only; mixed Solidity and Vyper go to `/compile`.
 #[post("/compile_solidity", format = "json", data = "<req>")]
 pub fn compile_solidity_route(
     req: Json<CompileRequest>,
 ) -> Result<Json<CompileResult>, status::BadRequest<String>> {
-    let result = compile(&req.files).map_err(|err| status::BadRequest(err.to_string()))?;
+    if let Some(file) = req.files.iter().find(|file| is_vyper(&file.name)) {
+        return Err(status::BadRequest(format!(
+            "{} is a Vyper file, use /compile instead",
+            file.name
+        )));
+    }
+    let result =
+        compile(&req.files, &req.settings).map_err(|err| status::BadRequest(err.to_string()))?;
 
     Ok(Json(result))
 }
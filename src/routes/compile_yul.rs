@@ -0,0 +1,56 @@
+use crate::{
+    compile::{settings::CompileSettings, yul::compile_yul},
+    gas::{deployed_code, BlockConfig, ExecutionEnv},
+};
+use alloy_primitives::{Bytes, U256};
+use rocket::{post, response::status, serde::json::Json};
+use serde::{Deserialize, Serialize};
+
+/// Gas the compiled code gets to deploy itself, a mainnet block's worth.
+const DEPLOY_GAS_LIMIT: u64 = 30_000_000;
+
+#[derive(Deserialize)]
+pub struct CompileYulRequest {
+    pub source: String,
+    /// Only the optimizer, EVM version and solc version apply to Yul.
+    #[serde(default)]
+    pub settings: CompileSettings,
+}
+
+#[derive(Serialize)]
+pub struct CompileYulResponse {
+    /// What solc produced, usually initcode for a Yul object.
+    pub bytecode: Bytes,
+    /// Code left behind by running `bytecode`, `None` if it doesn't deploy.
+    pub runtime: Option<Bytes>,
+}
+
+#[post("/compile_yul", format = "json", data = "<req>")]
+pub fn compile_yul_route(
+    req: Json<CompileYulRequest>,
+) -> Result<Json<CompileYulResponse>, status::BadRequest<Option<String>>> {
+    let bytecode = compile_yul(&req.source, &req.settings)
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    let block = BlockConfig {
+        gas_limit: Some(U256::from(DEPLOY_GAS_LIMIT)),
+        ..Default::default()
+    };
+    let runtime = deployed_code(bytecode.clone(), &ExecutionEnv::new(&block, None)).ok();
+    Ok(Json(CompileYulResponse { bytecode, runtime }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deploy_runs_out_of_gas_instead_of_spinning() {
+        let req = CompileYulRequest {
+            source: "{ for { } 1 { } { } }".to_string(),
+            settings: CompileSettings::default(),
+        };
+        let response = compile_yul_route(Json(req)).unwrap();
+        assert!(!response.bytecode.is_empty());
+        assert!(response.runtime.is_none());
+    }
+}
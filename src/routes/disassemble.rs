@@ -0,0 +1,16 @@
+use crate::bytecode::{disassemble, Disassembly};
+use alloy_primitives::Bytes;
+use rocket::{post, serde::json::Json};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct DisassembleRequest {
+    /// Runtime code or initcode. Trailing metadata is split off only if it
+    /// decodes as solc or vyper metadata.
+    pub bytecode: Bytes,
+}
+
+#[post("/disassemble", format = "json", data = "<req>")]
+pub fn disassemble_route(req: Json<DisassembleRequest>) -> Json<Disassembly> {
+    Json(disassemble(&req.bytecode))
+}
@@ -1,13 +1,31 @@
-use crate::gas::{execute_calldatas, Call};
+use crate::{
+    abi::append_constructor_args,
+    gas::{
+        execute_calldatas, BlockConfig, Call, ExecutionEnv, ExecutionResult, Hardfork,
+        StateOverride, TraceOptions,
+    },
+};
+use alloy_json_abi::JsonAbi;
 use alloy_primitives::hex;
-use revm::primitives::{Bytecode, ExecutionResult};
+use revm::primitives::Bytecode;
 use rocket::{post, response::status, serde::json::Json};
 use serde::Deserialize;
+use serde_json::Value;
 
 #[derive(Deserialize)]
 pub struct ExecuteCalldatasRequest {
     pub bytecode: String,
     pub calls: Vec<Call>,
+    pub abi: Option<JsonAbi>,
+    pub constructor_args: Option<Vec<Value>>,
+    #[serde(default)]
+    pub block: BlockConfig,
+    pub hardfork: Option<Hardfork>,
+    /// Balances, nonces, storage and code to set up before deploying.
+    #[serde(default)]
+    pub state: StateOverride,
+    #[serde(flatten)]
+    pub trace: TraceOptions,
 }
 
 #[post("/execute_calldatas", format = "json", data = "<req>")]
@@ -20,7 +38,16 @@ pub fn execute_calldatas_route(
 
 fn handle(req: Json<ExecuteCalldatasRequest>) -> Result<Vec<ExecutionResult>, eyre::Error> {
     let bytecode = hex::decode(&req.bytecode).map_err(|err| eyre::eyre!(err.to_string()))?;
-    let result = execute_calldatas(Bytecode::new_raw(bytecode.into()), req.calls.clone())
-        .map_err(|err| eyre::eyre!(err.to_string()))?;
+    let initcode =
+        append_constructor_args(bytecode, req.abi.as_ref(), req.constructor_args.as_deref())?;
+    let (_, result) = execute_calldatas(
+        Bytecode::new_raw(initcode),
+        req.calls.clone(),
+        req.abi.as_ref(),
+        ExecutionEnv::new(&req.block, req.hardfork),
+        &req.state,
+        &req.trace,
+    )
+    .map_err(|err| eyre::eyre!(err.to_string()))?;
     Ok(result)
 }
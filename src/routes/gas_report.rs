@@ -0,0 +1,85 @@
+use crate::{
+    abi::append_constructor_args,
+    gas::{
+        execute_calldatas, execute_calldatas_fork, gas_report, BlockConfig, Call, ExecutionEnv,
+        ForkCall, GasReport, Hardfork, StateOverride, TraceOptions,
+    },
+};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{hex, Address, Bytes};
+use revm::primitives::Bytecode;
+use rocket::{post, response::status, serde::json::Json};
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct GasReportRequest {
+    pub bytecode: String,
+    pub calls: Vec<Call>,
+    pub abi: Option<JsonAbi>,
+    pub constructor_args: Option<Vec<Value>>,
+    #[serde(default)]
+    pub block: BlockConfig,
+    pub hardfork: Option<Hardfork>,
+    #[serde(default)]
+    pub state: StateOverride,
+}
+
+#[derive(Deserialize)]
+pub struct GasReportForkRequest {
+    pub bytecode: Bytes,
+    pub address: Address,
+    pub calls: Vec<ForkCall>,
+    /// Only used to name functions in the report.
+    pub abi: Option<JsonAbi>,
+    pub hardfork: Option<Hardfork>,
+}
+
+#[post("/gas_report", format = "json", data = "<req>")]
+pub fn gas_report_route(
+    req: Json<GasReportRequest>,
+) -> Result<Json<GasReport>, status::BadRequest<Option<String>>> {
+    let result = handle(req).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle(req: Json<GasReportRequest>) -> Result<GasReport, eyre::Error> {
+    let bytecode = hex::decode(&req.bytecode).map_err(|err| eyre::eyre!(err.to_string()))?;
+    let initcode =
+        append_constructor_args(bytecode, req.abi.as_ref(), req.constructor_args.as_deref())?;
+    let (deployment, results) = execute_calldatas(
+        Bytecode::new_raw(initcode),
+        req.calls.clone(),
+        req.abi.as_ref(),
+        ExecutionEnv::new(&req.block, req.hardfork),
+        &req.state,
+        &TraceOptions::default(),
+    )?;
+    Ok(gas_report(
+        &results,
+        req.abi.as_ref(),
+        Some(deployment.gas_used),
+        deployment.code_size,
+    ))
+}
+
+#[post("/gas_report_fork", format = "json", data = "<req>")]
+pub async fn gas_report_fork_route(
+    req: Json<GasReportForkRequest>,
+) -> Result<Json<GasReport>, status::BadRequest<Option<String>>> {
+    let results = execute_calldatas_fork(
+        req.bytecode.clone(),
+        req.address,
+        req.calls.clone(),
+        req.hardfork,
+        &TraceOptions::default(),
+    )
+    .await
+    .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(gas_report(
+        &results,
+        req.abi.as_ref(),
+        None,
+        req.bytecode.len(),
+    )))
+}
@@ -0,0 +1,13 @@
+use crate::compile::libraries::LibraryStore;
+use rocket::{get, response::status, serde::json::Json};
+use std::collections::BTreeMap;
+
+/// Vendored libraries and their versions, usable in compile settings.
+#[get("/libraries")]
+pub fn libraries_route(
+) -> Result<Json<BTreeMap<String, Vec<String>>>, status::BadRequest<Option<String>>> {
+    let libraries = LibraryStore::from_env()
+        .available()
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(libraries))
+}
@@ -1,8 +1,35 @@
+mod assemble;
 mod byteracing;
+mod compare;
+mod compile;
+mod compile_and_run;
 mod compile_solidity;
+mod compile_yul;
+mod disassemble;
 mod execute_calldatas;
 mod execute_calldatas_fork;
+mod gas_report;
+mod libraries;
+mod session;
+mod solc_versions;
+mod source_gas;
+mod verify;
+pub use assemble::assemble_route;
 pub use byteracing::byterace_route;
+pub use compare::compare_route;
+pub use compile::compile_route;
+pub use compile_and_run::compile_and_run_route;
 pub use compile_solidity::compile_solidity_route;
+pub use compile_yul::compile_yul_route;
+pub use disassemble::disassemble_route;
 pub use execute_calldatas::execute_calldatas_route;
 pub use execute_calldatas_fork::execute_calldatas_fork_route;
+pub use gas_report::{gas_report_fork_route, gas_report_route};
+pub use libraries::libraries_route;
+pub use session::{
+    create_session_route, delete_session_route, list_sessions_route, session_call_route,
+    session_deploy_route,
+};
+pub use solc_versions::solc_versions_route;
+pub use source_gas::source_gas_route;
+pub use verify::verify_route;
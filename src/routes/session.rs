@@ -0,0 +1,137 @@
+use crate::{
+    abi::append_constructor_args,
+    gas::{deploy, encode_call, execute_call, Call, ExecutionResult, TraceOptions},
+    session::{SessionInfo, SessionStore},
+};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{hex, Address};
+use rocket::{delete, get, post, response::status, serde::json::Json, State};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct SessionDeployRequest {
+    pub bytecode: String,
+    pub abi: Option<JsonAbi>,
+    pub constructor_args: Option<Vec<Value>>,
+}
+
+#[derive(Serialize)]
+pub struct SessionDeployResponse {
+    pub address: Address,
+}
+
+#[derive(Deserialize)]
+pub struct SessionCallRequest {
+    /// Contract to call, defaults to the most recently deployed one.
+    pub address: Option<Address>,
+    pub calls: Vec<Call>,
+    pub abi: Option<JsonAbi>,
+    #[serde(flatten)]
+    pub trace: TraceOptions,
+}
+
+#[post("/sessions")]
+pub fn create_session_route(
+    store: &State<SessionStore>,
+) -> Result<Json<SessionInfo>, status::BadRequest<Option<String>>> {
+    let info = store
+        .create()
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(info))
+}
+
+#[get("/sessions")]
+pub fn list_sessions_route(
+    store: &State<SessionStore>,
+) -> Result<Json<Vec<SessionInfo>>, status::BadRequest<Option<String>>> {
+    let sessions = store
+        .list()
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(sessions))
+}
+
+#[delete("/sessions/<id>")]
+pub fn delete_session_route(
+    id: &str,
+    store: &State<SessionStore>,
+) -> Result<status::NoContent, status::BadRequest<Option<String>>> {
+    store
+        .delete(id)
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(status::NoContent)
+}
+
+#[post("/sessions/<id>/deploy", format = "json", data = "<req>")]
+pub fn session_deploy_route(
+    id: &str,
+    req: Json<SessionDeployRequest>,
+    store: &State<SessionStore>,
+) -> Result<Json<SessionDeployResponse>, status::BadRequest<Option<String>>> {
+    let address =
+        handle_deploy(id, req, store).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(SessionDeployResponse { address }))
+}
+
+#[post("/sessions/<id>/call", format = "json", data = "<req>")]
+pub fn session_call_route(
+    id: &str,
+    req: Json<SessionCallRequest>,
+    store: &State<SessionStore>,
+) -> Result<Json<Vec<ExecutionResult>>, status::BadRequest<Option<String>>> {
+    let result =
+        handle_call(id, req, store).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle_deploy(
+    id: &str,
+    req: Json<SessionDeployRequest>,
+    store: &State<SessionStore>,
+) -> Result<Address, eyre::Error> {
+    let bytecode = hex::decode(&req.bytecode).map_err(|err| eyre::eyre!(err.to_string()))?;
+    let initcode =
+        append_constructor_args(bytecode, req.abi.as_ref(), req.constructor_args.as_deref())?;
+    store.with_session(id, |session| {
+        let address = deploy(initcode, &session.env, &mut session.db)?.address;
+        session.contracts.push(address);
+        Ok(address)
+    })
+}
+
+fn handle_call(
+    id: &str,
+    req: Json<SessionCallRequest>,
+    store: &State<SessionStore>,
+) -> Result<Vec<ExecutionResult>, eyre::Error> {
+    // Encode everything first, so a bad call doesn't leave the session with
+    // only the calls before it applied
+    let calls = req
+        .calls
+        .iter()
+        .cloned()
+        .map(|call| encode_call(call, req.abi.as_ref()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    store.with_session(id, |session| {
+        let address = req
+            .address
+            .or(session.contracts.last().copied())
+            .ok_or(eyre::eyre!(
+                "No address given and nothing deployed in session"
+            ))?;
+        calls
+            .into_iter()
+            .map(|call| {
+                execute_call(
+                    address,
+                    call,
+                    req.abi.as_ref(),
+                    &mut session.env,
+                    &req.trace,
+                    &mut session.db,
+                )
+            })
+            .collect()
+    })
+}
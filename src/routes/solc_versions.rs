@@ -0,0 +1,12 @@
+use crate::compile::solc_store::SolcStore;
+use rocket::{get, response::status, serde::json::Json};
+use semver::Version;
+
+/// solc versions installed in the local compiler store.
+#[get("/solc_versions")]
+pub fn solc_versions_route() -> Result<Json<Vec<Version>>, status::BadRequest<Option<String>>> {
+    let versions = SolcStore::from_env()
+        .installed()
+        .map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(versions))
+}
@@ -0,0 +1,143 @@
+use crate::{
+    compile::{
+        settings::CompileSettings,
+        solidity::{compile, virtual_path, SolidityFile},
+    },
+    gas::{
+        execute_calldatas, BlockConfig, Call, ExecutionEnv, ExecutionResult, GasHeatMap, Hardfork,
+        SourceFile, SourceGasAttribution, StateOverride, StepTraceOptions, TraceOptions,
+    },
+};
+use revm::primitives::Bytecode;
+use rocket::{post, response::status, serde::json::Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct SourceGasRequest {
+    pub files: Vec<SolidityFile>,
+    #[serde(default)]
+    pub settings: CompileSettings,
+    /// Contract to deploy, as `Name` or `File.sol:Name`.
+    pub contract: String,
+    pub constructor_args: Option<Vec<Value>>,
+    pub calls: Vec<Call>,
+    #[serde(default)]
+    pub block: BlockConfig,
+    pub hardfork: Option<Hardfork>,
+    #[serde(default)]
+    pub state: StateOverride,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceGasResponse {
+    pub results: Vec<ExecutionResult>,
+    /// Gas per source line, summed over all calls.
+    pub heat_map: GasHeatMap,
+}
+
+#[post("/source_gas", format = "json", data = "<req>")]
+pub fn source_gas_route(
+    req: Json<SourceGasRequest>,
+) -> Result<Json<SourceGasResponse>, status::BadRequest<Option<String>>> {
+    let result = handle(req).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle(req: Json<SourceGasRequest>) -> Result<SourceGasResponse, eyre::Error> {
+    let output = compile(&req.files, &req.settings)?;
+    if output.has_errors() {
+        let errors: Vec<_> = output.errors.iter().map(|err| err.to_string()).collect();
+        return Err(eyre::eyre!("Compilation failed:\n{}", errors.join("\n")));
+    }
+
+    let contract = output.find_contract(&req.contract)?;
+    let (runtime, source_map) = contract
+        .runtime_source_map()
+        .ok_or_else(|| eyre::eyre!("No runtime source map for {}", req.contract))?;
+    let sources: Vec<_> = output
+        .source_ids(contract.version)
+        .into_iter()
+        .filter_map(|(id, path)| {
+            let path = output.source_path(path)?;
+            let file = req
+                .files
+                .iter()
+                .find(|file| virtual_path(&file.name).is_ok_and(|name| name == path))?;
+            Some(SourceFile {
+                id,
+                name: path.to_string_lossy().into_owned(),
+                content: file.content.clone(),
+            })
+        })
+        .collect();
+
+    let initcode = contract.initcode_with_args(req.constructor_args.as_deref())?;
+    let trace = TraceOptions {
+        step_trace: Some(StepTraceOptions {
+            enable_memory: false,
+            disable_stack: true,
+            disable_storage: true,
+        }),
+        ..Default::default()
+    };
+    let (_, mut results) = execute_calldatas(
+        Bytecode::new_raw(initcode),
+        req.calls.clone(),
+        contract.abi(),
+        ExecutionEnv::new(&req.block, req.hardfork),
+        &req.state,
+        &trace,
+    )?;
+
+    let mut attribution = SourceGasAttribution::new(runtime, source_map, &sources);
+    for result in &mut results {
+        // Only needed for the heat map, and large
+        if let Some(logs) = result.struct_logs.take() {
+            attribution.add(&logs);
+        }
+    }
+
+    Ok(SourceGasResponse {
+        results,
+        heat_map: attribution.finish(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_gas_is_attributed_to_the_compiled_file() {
+        let other = "pragma solidity ^0.8.0;\ncontract Other { function f() public {} }\n";
+        let target = "pragma solidity ^0.8.0;\n\
+            contract A {\n\
+            \x20   uint256 public count;\n\
+            \x20   function bump() public {\n\
+            \x20       count += 2;\n\
+            \x20   }\n\
+            }\n";
+        let req: SourceGasRequest = serde_json::from_value(json!({
+            "files": [
+                { "name": "A.sol", "content": other },
+                { "name": "./src/A.sol", "content": other },
+                { "name": "lib/A.sol", "content": target },
+            ],
+            "contract": "lib/A.sol:A",
+            "calls": [{ "function": "bump" }],
+        }))
+        .unwrap();
+
+        let response = handle(Json(req)).unwrap();
+        let lines = &response.heat_map.lines;
+        assert!(
+            lines.iter().all(|line| line.file == "lib/A.sol"),
+            "{:?}",
+            lines
+        );
+        assert!(lines.iter().any(|line| line.line == 5 && line.gas > 20_000));
+    }
+}
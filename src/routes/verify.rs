@@ -0,0 +1,102 @@
+use crate::{
+    bytecode::{compare_immutables, split_metadata, verify_runtime, ImmutableValue, Verification},
+    compile::{
+        settings::CompileSettings,
+        solidity::{compile, SolidityFile},
+    },
+    gas::{deployed_code, ExecutionEnv},
+};
+use alloy_primitives::Bytes;
+use rocket::{post, response::status, serde::json::Json};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Deserialize)]
+pub struct VerifyRequest {
+    pub files: Vec<SolidityFile>,
+    #[serde(default)]
+    pub settings: CompileSettings,
+    /// Contract to verify, as `Name` or `File.sol:Name`.
+    pub contract: String,
+    /// Runtime code as deployed.
+    pub deployed_bytecode: Bytes,
+    /// Input of the creation transaction, to recover constructor arguments.
+    pub creation_bytecode: Option<Bytes>,
+    /// Constructor arguments as JSON, when the creation input isn't at hand.
+    pub constructor_args: Option<Vec<Value>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyResponse {
+    #[serde(flatten)]
+    pub verification: Verification,
+    pub constructor_args: Option<Bytes>,
+    /// Immutables as filled in by running the constructor here, when its
+    /// arguments are known. Informational only: values derived from
+    /// `msg.sender`, `address(this)` or the block differ from the real
+    /// deployment without the code being wrong.
+    pub immutables: Option<Vec<ImmutableValue>>,
+}
+
+#[post("/verify", format = "json", data = "<req>")]
+pub fn verify_route(
+    req: Json<VerifyRequest>,
+) -> Result<Json<VerifyResponse>, status::BadRequest<Option<String>>> {
+    let result = handle(req).map_err(|err| status::BadRequest(Some(err.to_string())))?;
+    Ok(Json(result))
+}
+
+fn handle(req: Json<VerifyRequest>) -> Result<VerifyResponse, eyre::Error> {
+    let output = compile(&req.files, &req.settings)?;
+    if output.has_errors() {
+        let errors: Vec<_> = output.errors.iter().map(|err| err.to_string()).collect();
+        return Err(eyre::eyre!("Compilation failed:\n{}", errors.join("\n")));
+    }
+    let contract = output.find_contract(&req.contract)?;
+    let initcode = contract.initcode()?;
+
+    let constructor_args = match (&req.creation_bytecode, &req.constructor_args) {
+        (Some(creation), _) => {
+            // The runtime's metadata at the end of the initcode may differ
+            // while the code itself matches
+            let (code, _) = split_metadata(initcode);
+            if !creation.starts_with(code) || creation.len() < initcode.len() {
+                return Err(eyre::eyre!(
+                    "Creation bytecode doesn't start with the compiled initcode of {}",
+                    req.contract
+                ));
+            }
+            Some(creation.slice(initcode.len()..))
+        }
+        (None, Some(args)) => {
+            let with_args = contract.initcode_with_args(Some(args))?;
+            Some(with_args.slice(initcode.len()..))
+        }
+        (None, None) => None,
+    };
+
+    // Immutables are always masked, the constructor can't be run here in the
+    // deployment's environment
+    let expected = contract
+        .runtime()
+        .ok_or_else(|| eyre::eyre!("No runtime bytecode for {}", req.contract))?;
+    let ranges = contract.immutable_ranges();
+    let verification = verify_runtime(expected, &req.deployed_bytecode, &ranges);
+
+    let immutables = constructor_args.as_ref().and_then(|args| {
+        let initcode = [initcode.as_ref(), args.as_ref()].concat();
+        let deployed = deployed_code(initcode.into(), &ExecutionEnv::default()).ok()?;
+        Some(compare_immutables(
+            &deployed,
+            &req.deployed_bytecode,
+            &ranges,
+        ))
+    });
+
+    Ok(VerifyResponse {
+        verification,
+        constructor_args,
+        immutables,
+    })
+}
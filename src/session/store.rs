@@ -0,0 +1,217 @@
+use alloy_primitives::Address;
+use revm::{db::CacheDB, InMemoryDB};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, TryLockError},
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+use crate::gas::ExecutionEnv;
+
+/// Upper bound on live sessions so a misbehaving client can't exhaust memory.
+const MAX_SESSIONS: usize = 1_000;
+
+/// A long-lived world state that survives across requests.
+#[derive(Debug, Default)]
+pub struct Session {
+    pub db: CacheDB<InMemoryDB>,
+    /// Carries `warp`/`roll` from one request to the next.
+    pub env: ExecutionEnv,
+    pub contracts: Vec<Address>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionInfo {
+    pub id: String,
+    /// `None` while the session is busy running a call.
+    pub contracts: Option<Vec<Address>>,
+    pub busy: bool,
+    pub idle_secs: u64,
+    pub expires_in_secs: u64,
+}
+
+struct Entry {
+    session: Arc<Mutex<Session>>,
+    last_used: Instant,
+}
+
+/// Server-side store of sessions, evicting any session that has been idle for
+/// longer than `ttl`. Eviction happens lazily whenever the store is accessed.
+pub struct SessionStore {
+    entries: Mutex<HashMap<String, Entry>>,
+    ttl: Duration,
+}
+
+impl SessionStore {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+        }
+    }
+
+    pub fn create(&self) -> Result<SessionInfo, eyre::Error> {
+        let mut entries = self.lock()?;
+        self.evict_expired(&mut entries);
+        if entries.len() >= MAX_SESSIONS {
+            return Err(eyre::eyre!(
+                "Too many active sessions (max {}), delete one first",
+                MAX_SESSIONS
+            ));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let entry = Entry {
+            session: Arc::new(Mutex::new(Session::default())),
+            last_used: Instant::now(),
+        };
+        let info = self.info(&id, &entry.session, entry.last_used)?;
+        entries.insert(id, entry);
+        Ok(info)
+    }
+
+    pub fn list(&self) -> Result<Vec<SessionInfo>, eyre::Error> {
+        // Sessions are looked at only after releasing the store, so a long
+        // call on one session doesn't hold up every other request
+        let sessions: Vec<_> = {
+            let mut entries = self.lock()?;
+            self.evict_expired(&mut entries);
+            entries
+                .iter()
+                .map(|(id, entry)| (id.clone(), entry.session.clone(), entry.last_used))
+                .collect()
+        };
+        sessions
+            .iter()
+            .map(|(id, session, last_used)| self.info(id, session, *last_used))
+            .collect()
+    }
+
+    pub fn delete(&self, id: &str) -> Result<(), eyre::Error> {
+        let mut entries = self.lock()?;
+        self.evict_expired(&mut entries);
+        entries
+            .remove(id)
+            .map(|_| ())
+            .ok_or(eyre::eyre!("Session {} not found", id))
+    }
+
+    /// Runs `f` against the session's state and refreshes its TTL. The store
+    /// itself is only locked while looking the session up, so requests for
+    /// different sessions don't block each other.
+    pub fn with_session<T>(
+        &self,
+        id: &str,
+        f: impl FnOnce(&mut Session) -> Result<T, eyre::Error>,
+    ) -> Result<T, eyre::Error> {
+        let session = {
+            let mut entries = self.lock()?;
+            self.evict_expired(&mut entries);
+            let entry = entries
+                .get_mut(id)
+                .ok_or(eyre::eyre!("Session {} not found", id))?;
+            entry.last_used = Instant::now();
+            entry.session.clone()
+        };
+
+        let mut session = session
+            .lock()
+            .map_err(|_| eyre::eyre!("Session {} is poisoned", id))?;
+        f(&mut session)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, Entry>>, eyre::Error> {
+        self.entries
+            .lock()
+            .map_err(|_| eyre::eyre!("Session store is poisoned"))
+    }
+
+    fn evict_expired(&self, entries: &mut HashMap<String, Entry>) {
+        entries.retain(|_, entry| entry.last_used.elapsed() < self.ttl);
+    }
+
+    /// Doesn't wait for a session that is running a call, it's reported as
+    /// busy instead.
+    fn info(
+        &self,
+        id: &str,
+        session: &Mutex<Session>,
+        last_used: Instant,
+    ) -> Result<SessionInfo, eyre::Error> {
+        let contracts = match session.try_lock() {
+            Ok(session) => Some(session.contracts.clone()),
+            Err(TryLockError::WouldBlock) => None,
+            Err(TryLockError::Poisoned(_)) => {
+                return Err(eyre::eyre!("Session {} is poisoned", id))
+            }
+        };
+        let idle = last_used.elapsed();
+        Ok(SessionInfo {
+            id: id.to_string(),
+            busy: contracts.is_none(),
+            contracts,
+            idle_secs: idle.as_secs(),
+            expires_in_secs: self.ttl.saturating_sub(idle).as_secs(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_lifecycle() {
+        let store = SessionStore::new(Duration::from_secs(60));
+        let info = store.create().unwrap();
+
+        store
+            .with_session(&info.id, |session| {
+                session.contracts.push(Address::ZERO);
+                Ok(())
+            })
+            .unwrap();
+
+        let sessions = store.list().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].contracts, Some(vec![Address::ZERO]));
+
+        store.delete(&info.id).unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.delete(&info.id).is_err());
+    }
+
+    #[test]
+    fn test_expired_sessions_are_evicted() {
+        let store = SessionStore::new(Duration::ZERO);
+        let info = store.create().unwrap();
+
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.with_session(&info.id, |_| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_busy_session_does_not_block_store() {
+        let store = SessionStore::new(Duration::from_secs(60));
+        let busy = store.create().unwrap();
+        let idle = store.create().unwrap();
+
+        store
+            .with_session(&busy.id, |_| {
+                // Runs while `busy` is locked, as during a long call
+                let sessions = store.list()?;
+                let info = |id: &str| sessions.iter().find(|info| info.id == id).unwrap();
+                assert!(info(&busy.id).busy);
+                assert_eq!(info(&busy.id).contracts, None);
+                assert!(!info(&idle.id).busy);
+                assert_eq!(info(&idle.id).contracts, Some(vec![]));
+
+                let other = store.create()?;
+                store.delete(&other.id)?;
+                Ok(())
+            })
+            .unwrap();
+    }
+}